@@ -0,0 +1,278 @@
+use fsrs::filter_outlier;
+use time::{Date, Duration, OffsetDateTime};
+
+use crate::{Error, Result, RevlogEntry, sort_revlogs};
+
+/// Resolves the UTC offset of a review time
+pub trait OffsetProvider {
+  /// UTC offset in seconds at `timestamp` (milliseconds since the epoch)
+  fn offset_seconds(&self, timestamp: i64) -> Result<i64>;
+}
+
+/// DST-aware offsets from the tz database
+impl OffsetProvider for jiff::tz::TimeZone {
+  fn offset_seconds(&self, timestamp: i64) -> Result<i64> {
+    let ts = jiff::Timestamp::from_millisecond(timestamp)
+      .map_err(|e| Error::InvalidTimestamp(e.to_string()))?;
+    Ok(self.to_offset(ts).seconds() as i64)
+  }
+}
+
+/// Look up an IANA time zone in the embedded tz database
+pub fn time_zone(timezone: &str) -> Result<jiff::tz::TimeZone> {
+  jiff::tz::TimeZone::get(timezone)
+    .map_err(|e| Error::InvalidArgument(format!("Unknown timezone {timezone}: {e}")))
+}
+
+/// An FSRS item together with the revlog row its last review comes from
+#[derive(Debug, Clone)]
+pub struct ItemEntry {
+  pub card_id: String,
+  /// Time of the item's last review, in milliseconds since the epoch
+  pub review_time: i64,
+  /// Index of the last review's row in the source data, excluding the header
+  pub row_index: usize,
+  pub item: fsrs::FSRSItem,
+}
+
+fn convert_to_date(
+  timestamp: i64,
+  next_day_starts_at: i64,
+  offset_provider: &dyn OffsetProvider,
+) -> Result<Date> {
+  let timestamp_secs = timestamp / 1000;
+  let dt = OffsetDateTime::from_unix_timestamp(timestamp_secs)
+    .map_err(|e| Error::InvalidTimestamp(e.to_string()))?;
+
+  let offset_seconds = offset_provider.offset_seconds(timestamp)?;
+  let adjusted_dt = dt + Duration::seconds(offset_seconds) - Duration::hours(next_day_starts_at);
+  Ok(adjusted_dt.date())
+}
+
+fn remove_revlog_before_last_first_learn(entries: &[RevlogEntry]) -> &[RevlogEntry] {
+  // 0 new, 1 learning, 2 review, 3 relearning
+  // Keep only entries from the last contiguous block of learning states (0 or 1)
+  let is_learning_state = |entry: &RevlogEntry| matches!(entry.review_state, 0 | 1);
+
+  let mut last_learning_block_start = None;
+  for i in (0..entries.len()).rev() {
+    if is_learning_state(&entries[i]) {
+      last_learning_block_start = Some(i);
+    } else if last_learning_block_start.is_some() {
+      break;
+    }
+  }
+
+  if let Some(start) = last_learning_block_start {
+    &entries[start..]
+  } else {
+    &[]
+  }
+}
+
+/// Turn the history of a single card into FSRS items
+fn convert_to_fsrs_items_internal(
+  entries: &[RevlogEntry],
+  next_day_starts_at: i64,
+  offset_provider: &dyn OffsetProvider,
+) -> Result<Vec<ItemEntry>> {
+  let entries = remove_revlog_before_last_first_learn(entries);
+
+  // days elapsed since the previous review
+  let mut last_intervals = vec![0; entries.len()];
+  if !entries.is_empty() {
+    let mut prev_date =
+      convert_to_date(entries[0].review_time, next_day_starts_at, offset_provider)?;
+    for (item, last_interval) in entries.iter().zip(&mut last_intervals).skip(1) {
+      let date_current = convert_to_date(item.review_time, next_day_starts_at, offset_provider)?;
+      *last_interval = (date_current - prev_date).whole_days() as i32;
+      prev_date = date_current;
+    }
+  }
+
+  Ok(
+    entries
+      .iter()
+      .enumerate()
+      .skip(1)
+      .filter(|&(idx, _)| last_intervals[idx] > 0)
+      .map(|(idx, entry)| {
+        let reviews = entries
+          .iter()
+          .zip(&last_intervals)
+          .take(idx + 1)
+          .map(|(r, &last_interval)| fsrs::FSRSReview {
+            rating: r.review_rating,
+            delta_t: last_interval.max(0) as u32,
+          })
+          .collect();
+        ItemEntry {
+          card_id: entry.card_id.clone(),
+          review_time: entry.review_time,
+          row_index: entry.row_index,
+          item: fsrs::FSRSItem { reviews },
+        }
+      })
+      .collect(),
+  )
+}
+
+/// Group revlog entries by card and turn each card's history into FSRS items,
+/// ordered by review time across cards.
+pub fn convert_revlogs_to_fsrs_items(
+  revlogs: Vec<RevlogEntry>,
+  next_day_starts_at: i64,
+  offset_provider: &dyn OffsetProvider,
+) -> Result<Vec<fsrs::FSRSItem>> {
+  let entries =
+    convert_revlogs_to_fsrs_entries(revlogs, next_day_starts_at, offset_provider, || true)?;
+  Ok(entries.into_iter().map(|entry| entry.item).collect())
+}
+
+/// Like `convert_revlogs_to_fsrs_items`, keeping the source of every item and
+/// calling `on_card` after every converted card.
+/// Conversion stops with `Error::Aborted` when `on_card` returns false.
+pub fn convert_revlogs_to_fsrs_entries(
+  mut revlogs: Vec<RevlogEntry>,
+  next_day_starts_at: i64,
+  offset_provider: &dyn OffsetProvider,
+  on_card: impl FnMut() -> bool,
+) -> Result<Vec<ItemEntry>> {
+  sort_revlogs(&mut revlogs);
+  convert_sorted_revlogs_to_fsrs_entries(&revlogs, next_day_starts_at, offset_provider, on_card)
+}
+
+/// Like `convert_revlogs_to_fsrs_entries`, for entries already ordered by `sort_revlogs`.
+pub fn convert_sorted_revlogs_to_fsrs_entries(
+  revlogs: &[RevlogEntry],
+  next_day_starts_at: i64,
+  offset_provider: &dyn OffsetProvider,
+  mut on_card: impl FnMut() -> bool,
+) -> Result<Vec<ItemEntry>> {
+  let mut entries = Vec::new();
+  // Group by card_id while maintaining time order
+  for card in revlogs.chunk_by(|a, b| a.card_id == b.card_id) {
+    entries.extend(convert_to_fsrs_items_internal(
+      card,
+      next_day_starts_at,
+      offset_provider,
+    )?);
+    if !on_card() {
+      return Err(Error::Aborted);
+    }
+  }
+
+  // Sort by review_time to maintain correct order across groups
+  entries.sort_by_cached_key(|entry| entry.review_time);
+
+  Ok(entries)
+}
+
+/// Drop outliers the way the optimizer does before training or evaluation
+pub fn prepare_items(train_data: Vec<fsrs::FSRSItem>) -> Vec<fsrs::FSRSItem> {
+  let (mut dataset_for_initialization, mut trainset): (Vec<fsrs::FSRSItem>, Vec<fsrs::FSRSItem>) =
+    train_data
+      .into_iter()
+      .partition(|item| item.long_term_review_cnt() == 1);
+  (dataset_for_initialization, trainset) = filter_outlier(dataset_for_initialization, trainset);
+  [dataset_for_initialization, trainset].concat()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const DAY: i64 = 86_400_000;
+
+  fn entry(card_id: &str, review_time: i64, review_rating: u32, review_state: u32) -> RevlogEntry {
+    RevlogEntry {
+      card_id: card_id.to_string(),
+      review_time,
+      review_rating,
+      review_state,
+      review_duration: 0,
+      row_index: 0,
+    }
+  }
+
+  fn reviews(items: &[fsrs::FSRSItem]) -> Vec<Vec<(u32, u32)>> {
+    items
+      .iter()
+      .map(|item| item.reviews.iter().map(|r| (r.rating, r.delta_t)).collect())
+      .collect()
+  }
+
+  #[test]
+  fn converts_each_card_into_items_ordered_by_review_time() {
+    let revlogs = vec![
+      entry("1", 3 * DAY, 1, 2),
+      entry("2", 0, 1, 1),
+      entry("1", 0, 3, 1),
+      entry("2", 60_000, 3, 1),
+      entry("1", DAY, 3, 2),
+      entry("2", 2 * DAY, 4, 2),
+    ];
+    let items = convert_revlogs_to_fsrs_items(revlogs, 0, &time_zone("UTC").unwrap()).unwrap();
+    assert_eq!(
+      reviews(&items),
+      [
+        vec![(3, 0), (3, 1)],
+        vec![(1, 0), (3, 0), (4, 2)],
+        vec![(3, 0), (3, 1), (1, 2)],
+      ]
+    );
+  }
+
+  #[test]
+  fn day_boundaries_follow_the_time_zone_and_next_day_start() {
+    // 23:00 and 01:00 UTC fall on two days in UTC, but on the same day in
+    // Asia/Shanghai (07:00 and 09:00 local) when days start at 4:00
+    let revlogs = || {
+      vec![
+        entry("1", 23 * 3_600_000, 3, 1),
+        entry("1", 25 * 3_600_000, 3, 2),
+      ]
+    };
+    let utc = convert_revlogs_to_fsrs_items(revlogs(), 0, &time_zone("UTC").unwrap()).unwrap();
+    assert_eq!(reviews(&utc), [vec![(3, 0), (3, 1)]]);
+    let shanghai =
+      convert_revlogs_to_fsrs_items(revlogs(), 4, &time_zone("Asia/Shanghai").unwrap()).unwrap();
+    assert!(shanghai.is_empty());
+  }
+
+  #[test]
+  fn drops_the_history_before_a_card_reset() {
+    let revlogs = vec![
+      entry("1", 0, 3, 1),
+      entry("1", DAY, 3, 2),
+      // card reset and learned again
+      entry("1", 5 * DAY, 3, 0),
+      entry("1", 7 * DAY, 4, 2),
+    ];
+    let items = convert_revlogs_to_fsrs_items(revlogs, 0, &time_zone("UTC").unwrap()).unwrap();
+    assert_eq!(reviews(&items), [vec![(3, 0), (4, 2)]]);
+  }
+
+  #[test]
+  fn entries_keep_their_source_and_stop_when_on_card_returns_false() {
+    let mut revlogs = vec![entry("1", 0, 3, 1), entry("1", DAY, 3, 2)];
+    revlogs[1].row_index = 1;
+    let tz = time_zone("UTC").unwrap();
+    let entries = convert_revlogs_to_fsrs_entries(revlogs.clone(), 0, &tz, || true).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].card_id, "1");
+    assert_eq!(entries[0].review_time, DAY);
+    assert_eq!(entries[0].row_index, 1);
+
+    let result = convert_revlogs_to_fsrs_entries(revlogs, 0, &tz, || false);
+    assert!(matches!(result, Err(Error::Aborted)));
+  }
+
+  #[test]
+  fn rejects_an_unknown_time_zone() {
+    assert!(matches!(
+      time_zone("Mars/Olympus_Mons"),
+      Err(Error::InvalidArgument(_))
+    ));
+  }
+}
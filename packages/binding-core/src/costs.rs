@@ -0,0 +1,96 @@
+use itertools::Itertools;
+
+use crate::{RevlogEntry, STATE_LEARNING, STATE_NEW, STATE_RELEARNING, STATE_REVIEW};
+
+#[derive(Debug, Clone)]
+pub struct ReviewCosts {
+  /// Probability of each first rating [again, hard, good, easy]
+  pub first_rating_prob: Vec<f64>,
+  /// Probability of each successful review rating [hard, good, easy]
+  pub review_rating_prob: Vec<f64>,
+  /// Median seconds spent on a learning card per rating [again, hard, good, easy]
+  pub learn_costs: Vec<f64>,
+  /// Median seconds spent on a review card per rating [again, hard, good, easy]
+  pub review_costs: Vec<f64>,
+  /// Median seconds spent on a relearning card per rating [again, hard, good, easy]
+  pub relearn_costs: Vec<f64>,
+}
+
+/// Reviews longer than this are treated as the user walking away and ignored
+const MAX_REVIEW_DURATION_MS: u32 = 20 * 60 * 1000;
+
+fn median(durations: &mut [u32]) -> Option<f64> {
+  if durations.is_empty() {
+    return None;
+  }
+  durations.sort_unstable();
+  let n = durations.len();
+  let median_ms = if n.is_multiple_of(2) {
+    (durations[n / 2 - 1] as f64 + durations[n / 2] as f64) / 2.0
+  } else {
+    durations[n / 2] as f64
+  };
+  Some(median_ms / 1000.0)
+}
+
+/// Turn counts into probabilities, keeping `default` when there is no data.
+fn probabilities(counts: &[usize], default: &[f32]) -> Vec<f64> {
+  let total: usize = counts.iter().sum();
+  if total == 0 {
+    return default.iter().map(|&x| x as f64).collect();
+  }
+  counts.iter().map(|&c| c as f64 / total as f64).collect()
+}
+
+/// Median duration per rating, keeping `default` for ratings without data.
+fn costs(durations: &mut [Vec<u32>; 4], default: &[f32; 4]) -> Vec<f64> {
+  durations
+    .iter_mut()
+    .zip(default)
+    .map(|(d, &fallback)| median(d).unwrap_or(fallback as f64))
+    .collect()
+}
+
+/// Per-rating review costs and rating probabilities for revlogs sorted by
+/// (card_id, review_time). Ratings without data fall back to the fsrs-rs
+/// simulator defaults.
+pub fn extract_review_costs(revlogs: &[RevlogEntry]) -> ReviewCosts {
+  let defaults = fsrs::SimulatorConfig::default();
+  let mut first_ratings = [0usize; 4];
+  let mut review_ratings = [0usize; 3];
+  let mut durations: [[Vec<u32>; 4]; 3] = Default::default();
+
+  // Group by card_id (already sorted by card_id, review_time)
+  for (_card_id, entries) in &revlogs.iter().chunk_by(|r| &r.card_id) {
+    let mut seen_first = false;
+    for entry in entries.filter(|e| (1..=4).contains(&e.review_rating)) {
+      let rating_idx = (entry.review_rating - 1) as usize;
+      let state_idx = match entry.review_state {
+        STATE_NEW | STATE_LEARNING => 0,
+        STATE_REVIEW => 1,
+        STATE_RELEARNING => 2,
+        _ => continue,
+      };
+
+      if state_idx == 0 && !seen_first {
+        first_ratings[rating_idx] += 1;
+        seen_first = true;
+      }
+      if state_idx == 1 && entry.review_rating > 1 {
+        review_ratings[rating_idx - 1] += 1;
+      }
+      if entry.review_duration > 0 && entry.review_duration <= MAX_REVIEW_DURATION_MS {
+        durations[state_idx][rating_idx].push(entry.review_duration);
+      }
+    }
+  }
+
+  let [mut learn, mut review, mut relearn] = durations;
+  ReviewCosts {
+    first_rating_prob: probabilities(&first_ratings, &defaults.first_rating_prob),
+    review_rating_prob: probabilities(&review_ratings, &defaults.review_rating_prob),
+    learn_costs: costs(&mut learn, &defaults.state_rating_costs[0]),
+    review_costs: costs(&mut review, &defaults.state_rating_costs[1]),
+    relearn_costs: costs(&mut relearn, &defaults.state_rating_costs[2]),
+  }
+}
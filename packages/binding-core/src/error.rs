@@ -0,0 +1,35 @@
+use std::fmt;
+
+use crate::CsvRowError;
+
+#[derive(Debug, Clone)]
+pub enum Error {
+  /// A malformed CSV row, when not parsing leniently
+  InvalidRow(CsvRowError),
+  /// The CSV header could not be read
+  InvalidCsv(String),
+  MissingColumn(String),
+  InvalidArgument(String),
+  InvalidTimestamp(String),
+  /// The offset provider failed to resolve a UTC offset
+  Offset(String),
+  /// A progress callback asked to stop
+  Aborted,
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::InvalidRow(row) => write!(f, "CSV deserialization error at {row}"),
+      Self::InvalidCsv(reason) => write!(f, "CSV deserialization error: {reason}"),
+      Self::MissingColumn(name) => write!(f, "CSV is missing column {name:?}"),
+      Self::InvalidArgument(reason) | Self::Offset(reason) => f.write_str(reason),
+      Self::InvalidTimestamp(reason) => write!(f, "Invalid timestamp: {reason}"),
+      Self::Aborted => f.write_str("CSV conversion aborted"),
+    }
+  }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
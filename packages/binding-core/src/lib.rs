@@ -0,0 +1,19 @@
+//! Revlog conversion and analysis shared by the FSRS Node binding.
+//!
+//! Everything here is plain Rust: the `fsrs-binding` crate wraps it for
+//! Node, and other Rust programs can depend on it directly to run exactly
+//! the same conversion and step-recommendation code.
+
+mod convert;
+mod costs;
+mod error;
+mod revlog;
+mod steps;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+pub use convert::*;
+pub use costs::*;
+pub use error::*;
+pub use revlog::*;
+pub use steps::*;
@@ -0,0 +1,412 @@
+use std::fmt;
+use std::str::FromStr;
+
+use csv::{ReaderBuilder, StringRecord};
+
+use crate::{Error, Result};
+
+// review_state values
+pub const STATE_NEW: u32 = 0;
+pub const STATE_LEARNING: u32 = 1;
+pub const STATE_REVIEW: u32 = 2;
+pub const STATE_RELEARNING: u32 = 3;
+
+#[derive(Debug, Clone)]
+pub struct RevlogEntry {
+  // card_id,review_time,review_rating,review_state,review_duration
+  pub card_id: String,
+  /// Milliseconds since the Unix epoch
+  pub review_time: i64,
+  pub review_rating: u32,
+  pub review_state: u32,
+  pub review_duration: u32,
+  /// Index of the row in the source data, excluding the header
+  pub row_index: usize,
+}
+
+/// Sort revlog entries by card, then by review time
+pub fn sort_revlogs(revlogs: &mut [RevlogEntry]) {
+  revlogs.sort_by_cached_key(|r| (r.card_id.clone(), r.review_time));
+}
+
+/// Header names of the revlog columns
+#[derive(Debug, Clone)]
+pub struct CsvColumns {
+  pub card_id: String,
+  pub review_time: String,
+  pub review_rating: String,
+  pub review_state: String,
+  pub review_duration: String,
+}
+
+impl Default for CsvColumns {
+  fn default() -> Self {
+    Self {
+      card_id: "card_id".to_string(),
+      review_time: "review_time".to_string(),
+      review_rating: "review_rating".to_string(),
+      review_state: "review_state".to_string(),
+      review_duration: "review_duration".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampUnit {
+  /// Seconds since the Unix epoch, fractions allowed
+  Seconds,
+  /// Milliseconds since the Unix epoch
+  #[default]
+  Milliseconds,
+  /// An ISO-8601 timestamp with offset
+  Iso8601,
+}
+
+impl FromStr for TimestampUnit {
+  type Err = Error;
+
+  fn from_str(unit: &str) -> Result<Self> {
+    match unit {
+      "s" => Ok(Self::Seconds),
+      "ms" => Ok(Self::Milliseconds),
+      "iso8601" => Ok(Self::Iso8601),
+      _ => Err(Error::InvalidArgument(format!(
+        "timestampUnit must be one of \"s\", \"ms\" or \"iso8601\", got {unit:?}"
+      ))),
+    }
+  }
+}
+
+/// Layout of a revlog CSV. The default matches the
+/// `card_id,review_time,review_rating,review_state,review_duration` export.
+#[derive(Debug, Clone)]
+pub struct CsvSchema {
+  pub columns: CsvColumns,
+  pub delimiter: u8,
+  pub timestamp_unit: TimestampUnit,
+}
+
+impl Default for CsvSchema {
+  fn default() -> Self {
+    Self {
+      columns: CsvColumns::default(),
+      delimiter: b',',
+      timestamp_unit: TimestampUnit::default(),
+    }
+  }
+}
+
+/// A CSV row that could not be parsed
+#[derive(Debug, Clone)]
+pub struct CsvRowError {
+  /// 1-based line number in the source data; the header is line 1
+  pub line: u32,
+  /// Name of the offending column, if known
+  pub column: Option<String>,
+  /// Raw value of the offending field, if known
+  pub value: Option<String>,
+  pub message: String,
+}
+
+impl fmt::Display for CsvRowError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "line {}", self.line)?;
+    if let Some(column) = &self.column {
+      write!(f, ", column {column}")?;
+    }
+    write!(f, ": {}", self.message)?;
+    if let Some(value) = &self.value {
+      write!(f, " (value: {value:?})")?;
+    }
+    Ok(())
+  }
+}
+
+impl CsvSchema {
+  fn column_names(&self) -> [&str; 5] {
+    [
+      &self.columns.card_id,
+      &self.columns.review_time,
+      &self.columns.review_rating,
+      &self.columns.review_state,
+      &self.columns.review_duration,
+    ]
+  }
+
+  /// Position of every mapped column in the header
+  fn column_indices(&self, headers: &StringRecord) -> Result<[usize; 5]> {
+    let mut indices = [0; 5];
+    for (index, name) in indices.iter_mut().zip(self.column_names()) {
+      *index = headers
+        .iter()
+        .position(|header| header == name)
+        .ok_or_else(|| Error::MissingColumn(name.to_string()))?;
+    }
+    Ok(indices)
+  }
+
+  fn parse_row(
+    &self,
+    record: &StringRecord,
+    indices: &[usize; 5],
+  ) -> Result<RevlogEntry, CsvRowError> {
+    let [
+      card_id,
+      review_time,
+      review_rating,
+      review_state,
+      review_duration,
+    ] = *indices;
+    Ok(RevlogEntry {
+      card_id: self.field(record, card_id, 0)?.to_string(),
+      review_time: self.parse_timestamp(record, review_time)?,
+      review_rating: self.parse_field(record, review_rating, 2)?,
+      review_state: self.parse_field(record, review_state, 3)?,
+      review_duration: self.parse_field(record, review_duration, 4)?,
+      row_index: 0,
+    })
+  }
+
+  /// `column` is the position in `column_names`, `index` the position in the record
+  fn row_error(
+    &self,
+    record: &StringRecord,
+    index: usize,
+    column: usize,
+    message: String,
+  ) -> CsvRowError {
+    CsvRowError {
+      line: record
+        .position()
+        .map(|pos| pos.line() as u32)
+        .unwrap_or_default(),
+      column: Some(self.column_names()[column].to_string()),
+      value: record.get(index).map(str::to_string),
+      message,
+    }
+  }
+
+  fn field<'r>(
+    &self,
+    record: &'r StringRecord,
+    index: usize,
+    column: usize,
+  ) -> Result<&'r str, CsvRowError> {
+    record
+      .get(index)
+      .ok_or_else(|| self.row_error(record, index, column, "missing field".to_string()))
+  }
+
+  fn parse_field<T>(
+    &self,
+    record: &StringRecord,
+    index: usize,
+    column: usize,
+  ) -> Result<T, CsvRowError>
+  where
+    T: FromStr,
+    T::Err: fmt::Display,
+  {
+    self
+      .field(record, index, column)?
+      .parse()
+      .map_err(|e: T::Err| self.row_error(record, index, column, e.to_string()))
+  }
+
+  /// Review time in milliseconds since the Unix epoch
+  fn parse_timestamp(&self, record: &StringRecord, index: usize) -> Result<i64, CsvRowError> {
+    const COLUMN: usize = 1;
+    match self.timestamp_unit {
+      TimestampUnit::Milliseconds => self.parse_field(record, index, COLUMN),
+      // allow fractional seconds
+      TimestampUnit::Seconds => {
+        let secs: f64 = self.parse_field(record, index, COLUMN)?;
+        let millis = (secs * 1000.0).round();
+        // `as` would turn NaN into 0 and saturate values out of range
+        let range = jiff::Timestamp::MIN.as_millisecond() as f64
+          ..=jiff::Timestamp::MAX.as_millisecond() as f64;
+        if !range.contains(&millis) {
+          return Err(self.row_error(record, index, COLUMN, "timestamp out of range".to_string()));
+        }
+        Ok(millis as i64)
+      }
+      TimestampUnit::Iso8601 => self
+        .parse_field::<jiff::Timestamp>(record, index, COLUMN)
+        .map(|ts| ts.as_millisecond()),
+    }
+  }
+}
+
+/// Parse revlog entries from CSV laid out as described by `schema`.
+/// Malformed rows fail the parse, or are skipped and returned when `lenient` is set.
+pub fn read_revlog_csv(
+  data: &[u8],
+  schema: &CsvSchema,
+  lenient: bool,
+) -> Result<(Vec<RevlogEntry>, Vec<CsvRowError>)> {
+  read_revlog_csv_with_progress(data, schema, lenient, || true)
+}
+
+/// Like `read_revlog_csv`, calling `on_row` after every parsed row.
+/// Parsing stops with `Error::Aborted` when `on_row` returns false.
+pub fn read_revlog_csv_with_progress(
+  data: &[u8],
+  schema: &CsvSchema,
+  lenient: bool,
+  mut on_row: impl FnMut() -> bool,
+) -> Result<(Vec<RevlogEntry>, Vec<CsvRowError>)> {
+  let mut rdr = ReaderBuilder::new()
+    .has_headers(true)
+    .delimiter(schema.delimiter)
+    .from_reader(data);
+  let headers = rdr
+    .headers()
+    .map_err(|e| Error::InvalidCsv(e.to_string()))?
+    .clone();
+  let indices = schema.column_indices(&headers)?;
+
+  let mut revlogs = Vec::new();
+  let mut skipped_rows = Vec::new();
+  let mut record = StringRecord::new();
+  let mut row_index = 0;
+  loop {
+    let entry = match rdr.read_record(&mut record) {
+      Ok(false) => break,
+      Ok(true) => schema.parse_row(&record, &indices),
+      Err(e) => Err(CsvRowError {
+        line: e
+          .position()
+          .map(|pos| pos.line() as u32)
+          .unwrap_or_default(),
+        column: None,
+        value: None,
+        message: e.to_string(),
+      }),
+    };
+    match entry {
+      Ok(mut entry) => {
+        entry.row_index = row_index;
+        revlogs.push(entry);
+      }
+      Err(row_error) => {
+        if !lenient {
+          return Err(Error::InvalidRow(row_error));
+        }
+        skipped_rows.push(row_error);
+      }
+    }
+    row_index += 1;
+    if !on_row() {
+      return Err(Error::Aborted);
+    }
+  }
+  Ok((revlogs, skipped_rows))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CSV: &str = "card_id,review_time,review_rating,review_state,review_duration
+1,1000,3,1,500
+1,2000,five,2,500
+2,3000,4,2,700
+";
+
+  fn summary(revlogs: &[RevlogEntry]) -> Vec<(&str, i64, u32, usize)> {
+    revlogs
+      .iter()
+      .map(|r| {
+        (
+          r.card_id.as_str(),
+          r.review_time,
+          r.review_rating,
+          r.row_index,
+        )
+      })
+      .collect()
+  }
+
+  #[test]
+  fn fails_on_the_first_malformed_row() {
+    let err = read_revlog_csv(CSV.as_bytes(), &CsvSchema::default(), false).unwrap_err();
+    let Error::InvalidRow(row) = err else {
+      panic!("unexpected error: {err}");
+    };
+    assert_eq!(row.line, 3);
+    assert_eq!(row.column.as_deref(), Some("review_rating"));
+    assert_eq!(row.value.as_deref(), Some("five"));
+  }
+
+  #[test]
+  fn lenient_mode_skips_malformed_rows() {
+    let (revlogs, skipped_rows) =
+      read_revlog_csv(CSV.as_bytes(), &CsvSchema::default(), true).unwrap();
+    assert_eq!(summary(&revlogs), [("1", 1000, 3, 0), ("2", 3000, 4, 2)]);
+    assert_eq!(skipped_rows.len(), 1);
+    assert_eq!(skipped_rows[0].line, 3);
+  }
+
+  #[test]
+  fn schema_maps_columns_delimiter_and_timestamp_unit() {
+    let schema = CsvSchema {
+      columns: CsvColumns {
+        card_id: "cid".to_string(),
+        review_time: "time".to_string(),
+        review_rating: "ease".to_string(),
+        review_state: "type".to_string(),
+        review_duration: "taken".to_string(),
+      },
+      delimiter: b';',
+      timestamp_unit: TimestampUnit::Seconds,
+    };
+    let csv = "taken;type;ease;time;cid\n100;1;3;1.5;7\n";
+    let (revlogs, _) = read_revlog_csv(csv.as_bytes(), &schema, false).unwrap();
+    assert_eq!(summary(&revlogs), [("7", 1500, 3, 0)]);
+    assert_eq!(revlogs[0].review_state, 1);
+    assert_eq!(revlogs[0].review_duration, 100);
+
+    let iso = CsvSchema {
+      timestamp_unit: TimestampUnit::Iso8601,
+      ..CsvSchema::default()
+    };
+    let csv = "card_id,review_time,review_rating,review_state,review_duration
+1,2024-01-01T00:00:01+08:00,3,1,0
+";
+    let (revlogs, _) = read_revlog_csv(csv.as_bytes(), &iso, false).unwrap();
+    assert_eq!(revlogs[0].review_time, 1_704_038_401_000);
+  }
+
+  #[test]
+  fn rejects_a_missing_column() {
+    let err = read_revlog_csv(b"card_id,review_time\n", &CsvSchema::default(), false).unwrap_err();
+    assert!(matches!(err, Error::MissingColumn(name) if name == "review_rating"));
+  }
+
+  #[test]
+  fn rejects_seconds_that_are_not_a_valid_time() {
+    let schema = CsvSchema {
+      timestamp_unit: TimestampUnit::Seconds,
+      ..CsvSchema::default()
+    };
+    for value in ["nan", "inf", "-inf", "1e300"] {
+      let csv = format!(
+        "card_id,review_time,review_rating,review_state,review_duration\n1,{value},3,1,0\n"
+      );
+      let (revlogs, skipped_rows) = read_revlog_csv(csv.as_bytes(), &schema, true).unwrap();
+      assert!(revlogs.is_empty(), "{value} was accepted");
+      assert_eq!(skipped_rows[0].value.as_deref(), Some(value));
+    }
+  }
+
+  #[test]
+  fn stops_when_on_row_returns_false() {
+    let mut rows = 0;
+    let result = read_revlog_csv_with_progress(CSV.as_bytes(), &CsvSchema::default(), true, || {
+      rows += 1;
+      rows < 2
+    });
+    assert!(matches!(result, Err(Error::Aborted)));
+    assert_eq!(rows, 2);
+  }
+}
@@ -0,0 +1,19 @@
+//! Revlog fixtures for the tests of crates built on this one.
+
+const DAY: i64 = 86_400_000;
+
+/// A revlog CSV large enough to train on: every card is learned, then
+/// reviewed after growing intervals, and every seventh card forgets at its
+/// third review
+pub fn synthetic_revlog(cards: i64) -> String {
+  let mut csv = String::from("card_id,review_time,review_rating,review_state,review_duration\n");
+  for card in 0..cards {
+    let start = 1_700_049_600_000 + card * 60_000;
+    csv.push_str(&format!("{card},{start},3,1,8000\n"));
+    for (review, days) in [1, 4, 10, 25].into_iter().enumerate() {
+      let rating = if review == 2 && card % 7 == 0 { 1 } else { 3 };
+      csv.push_str(&format!("{card},{},{rating},2,5000\n", start + days * DAY));
+    }
+  }
+  csv
+}
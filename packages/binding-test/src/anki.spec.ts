@@ -0,0 +1,75 @@
+import * as fs from 'node:fs'
+import * as os from 'node:os'
+import * as path from 'node:path'
+import { fileURLToPath } from 'node:url'
+import {
+  convertAnkiCollectionToFsrsItems,
+  FSRSBindingItem,
+} from '@open-spaced-repetition/binding'
+
+describe('convertAnkiCollectionToFsrsItems', () => {
+  const convert = (file: string) =>
+    convertAnkiCollectionToFsrsItems(file, 4, 'Asia/Shanghai', () => 480)
+
+  let tmpDir: string
+  beforeAll(() => {
+    tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'fsrs-anki-'))
+  })
+  afterAll(() => {
+    fs.rmSync(tmpDir, { recursive: true, force: true })
+  })
+
+  const reviews = (items: FSRSBindingItem[]) =>
+    items.map((item) => item.reviews.map((r) => [r.rating, r.deltaT]))
+
+  // The fixtures hold one revlog: card 1 has manual, cram and rescheduled rows
+  // besides its real reviews, card 2 is a plain history and card 3 was only crammed.
+  // Cards 4 and 5 were reset ("Forget"), which drops their earlier reviews;
+  // card 5 was not studied again.
+  const expected = [
+    [
+      [3, 0],
+      [3, 1],
+    ],
+    [
+      [1, 0],
+      [3, 0],
+      [2, 2],
+    ],
+    [
+      [3, 0],
+      [3, 1],
+      [4, 2],
+    ],
+    [
+      [3, 0],
+      [3, 2],
+    ],
+  ]
+
+  test.each(['collection.anki2', 'collection.colpkg'])(
+    'converts the reviews in %s the way Anki does',
+    (name) => {
+      const file = fileURLToPath(new URL(`./fixtures/${name}`, import.meta.url))
+      expect(reviews(convert(file))).toEqual(expected)
+    }
+  )
+
+  test('throws for a missing archive', () => {
+    expect(() => convert(path.join(tmpDir, 'missing.colpkg'))).toThrow(
+      'Failed to open Anki archive'
+    )
+  })
+
+  test('throws for an archive that is not a zip file', () => {
+    const file = path.join(tmpDir, 'broken.apkg')
+    fs.writeFileSync(file, 'not a zip')
+    expect(() => convert(file)).toThrow('Failed to read Anki archive')
+  })
+
+  test('throws for a file that is not an Anki collection', () => {
+    const file = path.join(tmpDir, 'collection.anki2')
+    fs.writeFileSync(file, 'not a database')
+    expect(() => convert(file)).toThrow()
+  })
+})
@@ -1,7 +1,13 @@
 import * as fs from 'node:fs'
 import * as path from 'node:path'
 import { fileURLToPath } from 'node:url'
-import { convertCsvToFsrsItems } from '@open-spaced-repetition/binding'
+import {
+  convertCsvToFsrsItemEntries,
+  convertCsvToFsrsItems,
+  convertCsvToFsrsItemsAsync,
+  convertCsvToFsrsItemsWithReport,
+  FSRSBindingItem,
+} from '@open-spaced-repetition/binding'
 import { getTimezoneOffset, parseCSVToFSRSItems } from './helpers/csv-parser.js'
 
 const __filename = fileURLToPath(import.meta.url)
@@ -32,6 +38,391 @@ describe('CSV Parser', () => {
     )
   })
 
+  test('native timezone resolution matches the JS offset provider', () => {
+    const csvBuffer = fs.readFileSync(testDataPath)
+    const withCallback = convertCsvToFsrsItems(
+      csvBuffer,
+      nextDayStartsAt,
+      timezone,
+      (ms, tz) => getTimezoneOffset(tz, ms)
+    )
+    const native = convertCsvToFsrsItems(csvBuffer, nextDayStartsAt, timezone)
+    expect(native.map((item) => item.toString())).toEqual(
+      withCallback.map((item) => item.toString())
+    )
+  })
+
+  test('native timezone resolution follows DST transitions', () => {
+    // Reviews at 04:30 local time, just after the day rollover, on both sides
+    // of the 2024-03-10 DST change in New York: 09:30 UTC (EST, UTC-5) and
+    // 08:30 UTC (EDT, UTC-4). A fixed UTC-5 offset would put the second
+    // review on the previous day.
+    const csv = Buffer.from(
+      [
+        'card_id,review_time,review_rating,review_state,review_duration',
+        `1,${Date.UTC(2024, 2, 9, 9, 30)},3,1,0`,
+        `1,${Date.UTC(2024, 2, 10, 8, 30)},3,2,0`,
+        `1,${Date.UTC(2024, 2, 11, 8, 30)},3,2,0`,
+      ].join('\n')
+    )
+    const items = convertCsvToFsrsItems(csv, 4, 'America/New_York')
+    expect(items.map((item) => item.current?.deltaT)).toEqual([1, 1])
+  })
+
+  test('item entries keep the card id, review time and source row', () => {
+    const csv = Buffer.from(
+      [
+        'card_id,review_time,review_rating,review_state,review_duration',
+        '2,0,3,1,0',
+        '1,0,1,1,0',
+        '1,86400000,3,2,0',
+        '2,172800000,3,2,0',
+        '1,259200000,3,2,0',
+      ].join('\n')
+    )
+    const entries = convertCsvToFsrsItemEntries(csv, 0, 'UTC')
+    expect(
+      entries.map(({ cardId, reviewTime, rowIndex }) => ({
+        cardId,
+        reviewTime,
+        rowIndex,
+      }))
+    ).toEqual([
+      { cardId: '1', reviewTime: 86400000, rowIndex: 2 },
+      { cardId: '2', reviewTime: 172800000, rowIndex: 3 },
+      { cardId: '1', reviewTime: 259200000, rowIndex: 4 },
+    ])
+    for (const entry of entries) {
+      expect(entry.item).toBeInstanceOf(FSRSBindingItem)
+    }
+    expect(entries.map((entry) => entry.item.toString())).toEqual(
+      convertCsvToFsrsItems(csv, 0, 'UTC').map((item) => item.toString())
+    )
+  })
+
+  test('async conversion matches the synchronous result', async () => {
+    const csvBuffer = fs.readFileSync(testDataPath)
+    const expected = convertCsvToFsrsItems(csvBuffer, nextDayStartsAt, timezone)
+
+    let last = { current: 0, total: 0 }
+    const items = await convertCsvToFsrsItemsAsync(
+      csvBuffer,
+      nextDayStartsAt,
+      timezone,
+      {
+        progress: (current: number, total: number) => {
+          expect(current).toBeLessThanOrEqual(total)
+          last = { current, total }
+        },
+        timeout: 10,
+      }
+    )
+    expect(items.map((item) => item.toString())).toEqual(
+      expected.map((item) => item.toString())
+    )
+    expect(last.total).toBeGreaterThan(0)
+    // the last partial batch is published too, so progress ends at the total
+    await vi.waitFor(() => expect(last.current).toBe(last.total))
+  })
+
+  test('returning false cancels async conversion', async () => {
+    // Large enough that conversion is still running when the first callback returns
+    const rows = ['card_id,review_time,review_rating,review_state,review_duration']
+    for (let i = 0; i < 2_000_000; i++) {
+      const card = Math.floor(i / 10)
+      const day = i % 10
+      rows.push(`${card},${day * 86_400_000},3,${day === 0 ? 1 : 2},1000`)
+    }
+    let calls = 0
+    const result = convertCsvToFsrsItemsAsync(
+      Buffer.from(rows.join('\n')),
+      nextDayStartsAt,
+      timezone,
+      {
+        progress: () => {
+          calls++
+          return false
+        },
+        timeout: 1,
+      }
+    )
+    await expect(result).rejects.toThrow('aborted')
+    expect(calls).toBeGreaterThan(0)
+  }, 60_000)
+
+  describe('schema', () => {
+    // Rewrite revlog.csv with renamed columns, another delimiter and time format
+    const rewriteCsv = (
+      header: string[],
+      delimiter: string,
+      formatTime: (ms: number) => string
+    ) => {
+      const [, ...rows] = fs
+        .readFileSync(testDataPath, 'utf8')
+        .trim()
+        .split(/\r?\n/)
+      return Buffer.from(
+        [
+          header.join(delimiter),
+          ...rows.map((row) => {
+            const [cardId, time, ...rest] = row.split(',')
+            return [cardId, formatTime(Number(time)), ...rest].join(delimiter)
+          }),
+        ].join('\n')
+      )
+    }
+    const columns = {
+      cardId: 'cid',
+      reviewTime: 'time',
+      reviewRating: 'ease',
+      reviewState: 'type',
+      reviewDuration: 'taken',
+    }
+    const expected = () =>
+      convertCsvToFsrsItems(
+        fs.readFileSync(testDataPath),
+        nextDayStartsAt,
+        timezone
+      ).map((item) => item.toString())
+
+    test('maps columns, delimiter and second timestamps', () => {
+      const csvBuffer = rewriteCsv(Object.values(columns), '\t', (ms) =>
+        String(ms / 1000)
+      )
+      const items = convertCsvToFsrsItems(
+        csvBuffer,
+        nextDayStartsAt,
+        timezone,
+        { schema: { columns, delimiter: '\t', timestampUnit: 's' } }
+      )
+      expect(items.map((item) => item.toString())).toEqual(expected())
+    })
+
+    test('parses ISO-8601 timestamps', () => {
+      const csvBuffer = rewriteCsv(
+        [
+          'card_id',
+          'review_time',
+          'review_rating',
+          'review_state',
+          'review_duration',
+        ],
+        ';',
+        (ms) => new Date(ms).toISOString()
+      )
+      const items = convertCsvToFsrsItems(
+        csvBuffer,
+        nextDayStartsAt,
+        timezone,
+        { schema: { delimiter: ';', timestampUnit: 'iso8601' } }
+      )
+      expect(items.map((item) => item.toString())).toEqual(expected())
+    })
+
+    test('takes an offset provider next to the schema', () => {
+      const csvBuffer = rewriteCsv(Object.values(columns), '\t', (ms) =>
+        String(ms)
+      )
+      let calls = 0
+      const items = convertCsvToFsrsItems(csvBuffer, nextDayStartsAt, timezone, {
+        schema: { columns, delimiter: '\t' },
+        offsetProvider: (ms, tz) => {
+          calls++
+          return getTimezoneOffset(tz, ms)
+        },
+      })
+      expect(items.map((item) => item.toString())).toEqual(expected())
+      expect(calls).toBeGreaterThan(0)
+    })
+
+    test('rejects non-finite and out-of-range second timestamps', () => {
+      const csvBuffer = Buffer.from(
+        [
+          'card_id,review_time,review_rating,review_state,review_duration',
+          '1,1700000000,3,1,1000',
+          '1,nan,3,2,1000',
+          '1,inf,3,2,1000',
+          '1,1e300,3,2,1000',
+        ].join('\n')
+      )
+      const schema = { timestampUnit: 's' }
+      expect(() =>
+        convertCsvToFsrsItems(csvBuffer, nextDayStartsAt, timezone, { schema })
+      ).toThrow('line 3, column review_time')
+
+      const report = convertCsvToFsrsItemsWithReport(
+        csvBuffer,
+        nextDayStartsAt,
+        timezone,
+        { schema, lenient: true }
+      )
+      expect(report.skippedRows.map((row) => [row.line, row.value])).toEqual([
+        [3, 'nan'],
+        [4, 'inf'],
+        [5, '1e300'],
+      ])
+    })
+
+    test('rejects a missing column or an invalid schema', () => {
+      const csvBuffer = fs.readFileSync(testDataPath)
+      const convert = (schema: object) =>
+        convertCsvToFsrsItems(
+          csvBuffer,
+          nextDayStartsAt,
+          timezone,
+          { schema }
+        )
+      expect(() => convert({ columns })).toThrow('CSV is missing column "cid"')
+      expect(() => convert({ delimiter: '||' })).toThrow('delimiter')
+      expect(() => convert({ timestampUnit: 'us' })).toThrow('timestampUnit')
+    })
+  })
+
+  describe('typed input', () => {
+    const [, ...rows] = fs
+      .readFileSync(testDataPath, 'utf8')
+      .trim()
+      .split(/\r?\n/)
+      .map((row) => row.split(',').map(Number))
+    const expected = () =>
+      convertCsvToFsrsItems(
+        fs.readFileSync(testDataPath),
+        nextDayStartsAt,
+        timezone
+      ).map((item) => item.toString())
+
+    test('accepts an array of revlog entries', () => {
+      const entries = rows.map(
+        ([cardId, reviewTime, reviewRating, reviewState, reviewDuration]) => ({
+          cardId,
+          reviewTime,
+          reviewRating,
+          reviewState,
+          reviewDuration,
+        })
+      )
+      const items = convertCsvToFsrsItems(entries, nextDayStartsAt, timezone)
+      expect(items.map((item) => item.toString())).toEqual(expected())
+    })
+
+    test('rejects CSV-only options', () => {
+      const entries = [
+        {
+          cardId: 1,
+          reviewTime: 0,
+          reviewRating: 3,
+          reviewState: 1,
+          reviewDuration: 0,
+        },
+      ]
+      expect(() =>
+        convertCsvToFsrsItems(entries, nextDayStartsAt, timezone, {
+          lenient: true,
+        })
+      ).toThrow('lenient and schema only apply to CSV input')
+      expect(() =>
+        convertCsvToFsrsItemsAsync(entries, nextDayStartsAt, timezone, {
+          schema: { delimiter: ';' },
+        })
+      ).toThrow('lenient and schema only apply to CSV input')
+    })
+
+    test('async conversion accepts typed input', async () => {
+      const entries = rows.map(
+        ([cardId, reviewTime, reviewRating, reviewState, reviewDuration]) => ({
+          cardId,
+          reviewTime,
+          reviewRating,
+          reviewState,
+          reviewDuration,
+        })
+      )
+      const items = await convertCsvToFsrsItemsAsync(
+        entries,
+        nextDayStartsAt,
+        timezone
+      )
+      expect(items.map((item) => item.toString())).toEqual(expected())
+    })
+
+    test('accepts columnar typed arrays', () => {
+      const columns = {
+        cardIds: BigInt64Array.from(rows, (row) => BigInt(row[0])),
+        reviewTimes: BigInt64Array.from(rows, (row) => BigInt(row[1])),
+        reviewRatings: Uint8Array.from(rows, (row) => row[2]),
+        reviewStates: Uint8Array.from(rows, (row) => row[3]),
+        reviewDurations: Uint32Array.from(rows, (row) => row[4]),
+      }
+      const items = convertCsvToFsrsItems(columns, nextDayStartsAt, timezone)
+      expect(items.map((item) => item.toString())).toEqual(expected())
+
+      expect(() =>
+        convertCsvToFsrsItems(
+          { ...columns, reviewRatings: columns.reviewRatings.subarray(1) },
+          nextDayStartsAt,
+          timezone
+        )
+      ).toThrow('revlog columns must have the same length')
+    })
+  })
+
+  describe('malformed rows', () => {
+    const csvBuffer = Buffer.from(
+      [
+        'card_id,review_time,review_rating,review_state,review_duration',
+        '1,1700000000000,3,1,1000',
+        '1,1700086400000,five,2,1000',
+        '1,1700172800000,3,2',
+        '1,1700259200000,3,2,1000',
+      ].join('\n')
+    )
+
+    test('fail with the line, column and value of the first bad row', () => {
+      expect(() =>
+        convertCsvToFsrsItems(csvBuffer, nextDayStartsAt, timezone)
+      ).toThrow(
+        'CSV deserialization error at line 3, column review_rating: '
+      )
+      expect(() =>
+        convertCsvToFsrsItems(csvBuffer, nextDayStartsAt, timezone)
+      ).toThrow('(value: "five")')
+    })
+
+    test('are skipped and reported in lenient mode', () => {
+      const report = convertCsvToFsrsItemsWithReport(
+        csvBuffer,
+        nextDayStartsAt,
+        timezone,
+        { lenient: true }
+      )
+      expect(report.skippedRows.length).toBe(2)
+      expect(report.skippedRows[0]).toMatchObject({
+        line: 3,
+        column: 'review_rating',
+        value: 'five',
+      })
+      expect(report.skippedRows[1].line).toBe(4)
+      expect(report.skippedRows[1].column).toBeUndefined()
+
+      const items = convertCsvToFsrsItems(
+        csvBuffer,
+        nextDayStartsAt,
+        timezone,
+        { lenient: true }
+      )
+      expect(report.items.map((item) => item.toString())).toEqual(
+        items.map((item) => item.toString())
+      )
+    })
+  })
+
+  test('throws for an unknown timezone', () => {
+    const csvBuffer = fs.readFileSync(testDataPath)
+    expect(() =>
+      convertCsvToFsrsItems(csvBuffer, nextDayStartsAt, 'Mars/Olympus_Mons')
+    ).toThrow('Unknown timezone')
+  })
+
   describe('getTimezoneOffset', () => {
     test('should parse GMT+8 format', () => {
       const offset = getTimezoneOffset('Asia/Shanghai', new Date('2024-01-01'))
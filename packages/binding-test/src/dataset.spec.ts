@@ -0,0 +1,143 @@
+import * as fs from 'node:fs'
+import {
+  convertCsvToFsrsItems,
+  evaluateWithTimeSeriesSplits,
+  FSRSBinding,
+  FSRSBindingDataset,
+  FSRSBindingItem,
+  FSRSBindingReview,
+  FSRSBindingRevlog,
+} from '@open-spaced-repetition/binding'
+
+describe('FSRSBindingDataset', () => {
+  const items = Array.from(
+    { length: 10 },
+    (_, i) =>
+      new FSRSBindingItem([
+        new FSRSBindingReview(3, 0),
+        new FSRSBindingReview((i % 4) + 1, i + 1),
+      ])
+  )
+  const deltaTs = (dataset: FSRSBindingDataset) =>
+    dataset.items().map((item) => item.current?.deltaT)
+
+  test('owns a copy of the items', () => {
+    const dataset = new FSRSBindingDataset(items)
+    expect(dataset.length).toBe(10)
+    expect(dataset.isEmpty()).toBe(false)
+    expect(dataset.items().map((item) => item.toString())).toEqual(
+      items.map((item) => item.toString())
+    )
+    expect(new FSRSBindingDataset([]).isEmpty()).toBe(true)
+  })
+
+  test('filter', () => {
+    const dataset = new FSRSBindingDataset(items)
+    const again = dataset.filter((item) => item.current?.rating === 1)
+    expect(deltaTs(again)).toEqual([1, 5, 9])
+  })
+
+  test('split keeps the order unless seeded', () => {
+    const dataset = new FSRSBindingDataset(items)
+    const [train, test] = dataset.split(0.8)
+    expect(deltaTs(train)).toEqual([1, 2, 3, 4, 5, 6, 7, 8])
+    expect(deltaTs(test)).toEqual([9, 10])
+
+    const [a, b] = dataset.split(0.5, 42)
+    expect(a.length).toBe(5)
+    expect([...deltaTs(a), ...deltaTs(b)].sort((x, y) => x! - y!)).toEqual([
+      1, 2, 3, 4, 5, 6, 7, 8, 9, 10,
+    ])
+    expect(deltaTs(dataset.split(0.5, 42)[0])).toEqual(deltaTs(a))
+
+    expect(() => dataset.split(1.5)).toThrow()
+  })
+
+  test('sample', () => {
+    const dataset = new FSRSBindingDataset(items)
+    const sample = dataset.sample(4, 7)
+    expect(sample.length).toBe(4)
+    const picked = deltaTs(sample)
+    expect(picked).toEqual([...picked].sort((x, y) => x! - y!))
+    expect(deltaTs(dataset.sample(4, 7))).toEqual(picked)
+    expect(dataset.sample(10).length).toBe(10)
+    expect(() => dataset.sample(11)).toThrow()
+  })
+
+  test('is accepted wherever an item array is', async () => {
+    const csvBuffer = fs.readFileSync(new URL('./revlog.csv', import.meta.url))
+    const allItems = convertCsvToFsrsItems(csvBuffer, 4, 'Asia/Shanghai')
+    const dataset = new FSRSBindingRevlog(csvBuffer).toFsrsDataset(
+      4,
+      'Asia/Shanghai'
+    )
+    expect(dataset.length).toBe(allItems.length)
+
+    const f = new FSRSBinding()
+    expect(f.evaluate(dataset)).toEqual(f.evaluate(allItems))
+    expect(f.universalMetrics(dataset)).toEqual(f.universalMetrics(allItems))
+    expect(await evaluateWithTimeSeriesSplits(dataset)).toEqual(
+      await evaluateWithTimeSeriesSplits(allItems)
+    )
+  }, 360_000)
+})
+
+describe('FSRSBindingDataset encoding', () => {
+  const dataset = new FSRSBindingDataset([
+    new FSRSBindingItem([
+      new FSRSBindingReview(3, 0),
+      new FSRSBindingReview(1, 4_000_000_000),
+    ]),
+    new FSRSBindingItem([]),
+    new FSRSBindingItem([new FSRSBindingReview(4, 127)]),
+  ])
+
+  test('round-trips exactly', () => {
+    const encoded = dataset.encode()
+    expect(encoded.subarray(0, 4).toString()).toBe('FSRS')
+    const decoded = FSRSBindingDataset.decode(encoded)
+    expect(decoded.items().map((item) => item.toString())).toEqual(
+      dataset.items().map((item) => item.toString())
+    )
+    expect(decoded.encode()).toEqual(encoded)
+  })
+
+  test('rejects foreign, newer or damaged data', () => {
+    const encoded = dataset.encode()
+    expect(() => FSRSBindingDataset.decode(Buffer.from('nope'))).toThrow(
+      'Not an encoded FSRS dataset'
+    )
+
+    const newer = Buffer.from(encoded)
+    newer.writeUInt16LE(2, 4)
+    expect(() => FSRSBindingDataset.decode(newer)).toThrow(
+      'Unsupported dataset format version 2'
+    )
+
+    expect(() =>
+      FSRSBindingDataset.decode(encoded.subarray(0, encoded.length - 1))
+    ).toThrow('Truncated dataset')
+    expect(() =>
+      FSRSBindingDataset.decode(Buffer.concat([encoded, Buffer.from([0])]))
+    ).toThrow('Trailing data')
+  })
+
+  test('rejects ratings that fromJSON rejects', () => {
+    const encoded = dataset.encode()
+    // magic, version and item count come first, then the first item's
+    // review count and its first rating
+    expect(encoded[11]).toBe(3)
+    for (const rating of [0, 5]) {
+      const corrupted = Buffer.from(encoded)
+      corrupted[11] = rating
+      expect(() => FSRSBindingDataset.decode(corrupted)).toThrow(
+        `Invalid rating ${rating} in dataset`
+      )
+      const json = JSON.parse(dataset.items()[0].toString())
+      json.reviews[0].rating = rating
+      expect(() => FSRSBindingItem.fromJSON(JSON.stringify(json))).toThrow(
+        'rating must be between 1 and 4'
+      )
+    }
+  })
+})
@@ -1,5 +1,7 @@
 import {
+  BindingItemState,
   BindingMemoryState,
+  BindingNextStates,
   convertCsvToFsrsItems,
   FSRSBinding,
   FSRSBindingItem,
@@ -73,6 +75,175 @@ describe('FSRS model', () => {
     expect(() => f.memoryStateFromSM2(2.5, 10, 1.0)).toThrow()
   })
 
+  test('memoryState replays the full review history', () => {
+    const f = new FSRSBinding()
+    const item = new FSRSBindingItem([
+      new FSRSBindingReview(3, 0),
+      new FSRSBindingReview(3, 3),
+      new FSRSBindingReview(1, 10),
+    ])
+
+    // stepping through nextStates review by review must give the same state
+    let expected = f.nextStates(null, 0.9, 0).good.memory
+    expected = f.nextStates(expected, 0.9, 3).good.memory
+    expected = f.nextStates(expected, 0.9, 10).again.memory
+
+    const m = f.memoryState(item)
+    expect(m).toBeInstanceOf(BindingMemoryState)
+    expect(m.stability).toBeCloseTo(expected.stability, 4)
+    expect(m.difficulty).toBeCloseTo(expected.difficulty, 4)
+
+    // a starting state seeds the first review instead of the initial state
+    const seed = f.memoryStateFromSM2(2.5, 10, 0.9)
+    const seeded = f.memoryState(
+      new FSRSBindingItem([new FSRSBindingReview(3, 10)]),
+      seed
+    )
+    const next = f.nextStates(seed, 0.9, 10).good.memory
+    expect(seeded.stability).toBeCloseTo(next.stability, 4)
+    expect(seeded.difficulty).toBeCloseTo(next.difficulty, 4)
+  })
+
+  test('memoryStateHistory returns a state per review', () => {
+    const f = new FSRSBinding()
+    const reviews = [
+      new FSRSBindingReview(3, 0),
+      new FSRSBindingReview(3, 3),
+      new FSRSBindingReview(1, 10),
+    ]
+    const history = f.memoryStateHistory(new FSRSBindingItem(reviews))
+    expect(history.length).toBe(reviews.length)
+    expect(history[0].retrievability).toBeUndefined()
+    for (const step of history.slice(1)) {
+      expect(step.retrievability).toBeGreaterThan(0)
+      expect(step.retrievability).toBeLessThanOrEqual(1)
+    }
+
+    // the last step matches the final memory state
+    const last = history[history.length - 1]
+    const m = f.memoryState(new FSRSBindingItem(reviews))
+    expect(last.stability).toBeCloseTo(m.stability, 4)
+    expect(last.difficulty).toBeCloseTo(m.difficulty, 4)
+    expect(last.rating).toBe(1)
+    expect(last.deltaT).toBe(10)
+  })
+
+  test('memoryStates computes one state per item', async () => {
+    const f = new FSRSBinding()
+    const items = [
+      new FSRSBindingItem([new FSRSBindingReview(3, 0)]),
+      new FSRSBindingItem([
+        new FSRSBindingReview(1, 0),
+        new FSRSBindingReview(3, 1),
+      ]),
+      new FSRSBindingItem([
+        new FSRSBindingReview(3, 0),
+        new FSRSBindingReview(3, 3),
+        new FSRSBindingReview(1, 10),
+      ]),
+    ]
+    const states = await f.memoryStates(items)
+    expect(states.length).toBe(items.length)
+    states.forEach((state, i) => {
+      expect(state).toBeInstanceOf(BindingMemoryState)
+      const expected = f.memoryState(items[i])
+      expect(state.stability).toBeCloseTo(expected.stability, 4)
+      expect(state.difficulty).toBeCloseTo(expected.difficulty, 4)
+    })
+  })
+
+  test('memoryStates rejects items without reviews', async () => {
+    const f = new FSRSBinding()
+    const items = [
+      new FSRSBindingItem([new FSRSBindingReview(3, 0)]),
+      new FSRSBindingItem([]),
+    ]
+    await expect(f.memoryStates(items)).rejects.toThrow(
+      'item 1 has no reviews',
+    )
+  })
+
+  test('retrievability and interval follow the forgetting curve', () => {
+    const f = new FSRSBinding()
+    const m = new BindingMemoryState(10, 5)
+
+    // by definition, retrievability is 0.9 after `stability` days
+    expect(m.retrievability(0, f.decay)).toBeCloseTo(1, 6)
+    expect(m.retrievability(10, f.decay)).toBeCloseTo(0.9, 4)
+    expect(m.retrievability(2.5, f.decay)).toBeGreaterThan(
+      m.retrievability(10, f.decay)
+    )
+    expect(m.interval(0.9, f.decay)).toBeCloseTo(10, 3)
+
+    // interval is the inverse of retrievability
+    for (const retention of [0.7, 0.8, 0.95]) {
+      const days = m.interval(retention, f.decay)
+      expect(m.retrievability(days, f.decay)).toBeCloseTo(retention, 5)
+    }
+
+    // pre-FSRS-6 decay
+    expect(m.retrievability(10, 0.5)).toBeCloseTo(0.9, 4)
+    expect(m.interval(0.9)).toBeCloseTo(10, 3)
+
+    expect(() => m.retrievability(-1)).toThrow()
+    expect(() => m.interval(1)).toThrow()
+    expect(() => m.interval(0.9, 0)).toThrow()
+  })
+
+  test('fromJSON rebuilds objects from toString', () => {
+    const review = new FSRSBindingReview(3, 5)
+    const fromReview = FSRSBindingReview.fromJSON(review.toString())
+    expect(fromReview).toBeInstanceOf(FSRSBindingReview)
+    expect(fromReview.rating).toBe(3)
+    expect(fromReview.deltaT).toBe(5)
+
+    const item = new FSRSBindingItem([
+      new FSRSBindingReview(1, 0),
+      new FSRSBindingReview(3, 2),
+    ])
+    const fromItem = FSRSBindingItem.fromJSON(item.toString())
+    expect(fromItem).toBeInstanceOf(FSRSBindingItem)
+    expect(fromItem.toString()).toBe(item.toString())
+
+    const memory = new BindingMemoryState(12.5, 4.25)
+    const fromMemory = BindingMemoryState.fromJSON(memory.toString())
+    expect(fromMemory).toBeInstanceOf(BindingMemoryState)
+    expect(fromMemory.toString()).toBe(memory.toString())
+
+    const nextStates = new FSRSBinding().nextStates(memory, 0.9, 3)
+    const fromNextStates = BindingNextStates.fromJSON(nextStates.toString())
+    expect(fromNextStates).toBeInstanceOf(BindingNextStates)
+    expect(fromNextStates.toString()).toBe(nextStates.toString())
+
+    const fromItemState = BindingItemState.fromJSON(nextStates.good.toString())
+    expect(fromItemState).toBeInstanceOf(BindingItemState)
+    expect(fromItemState.interval).toBe(nextStates.good.interval)
+    expect(fromItemState.memory.stability).toBe(
+      nextStates.good.memory.stability
+    )
+  })
+
+  test('fromJSON validates its input', () => {
+    expect(() => FSRSBindingReview.fromJSON('not json')).toThrow(
+      'Invalid FSRSBindingReview JSON'
+    )
+    expect(() =>
+      FSRSBindingReview.fromJSON('{"rating":5,"delta_t":0}')
+    ).toThrow('rating must be between 1 and 4')
+    expect(() =>
+      FSRSBindingItem.fromJSON('{"reviews":[{"rating":3,"delta_t":-1}]}')
+    ).toThrow('Invalid FSRSBindingItem JSON')
+    expect(() =>
+      BindingMemoryState.fromJSON('{"stability":0,"difficulty":5}')
+    ).toThrow('stability must be a positive number')
+    // another type's JSON is rejected
+    expect(() =>
+      BindingMemoryState.fromJSON(
+        new FSRSBinding().nextStates(null, 0.9, 0).good.toString()
+      )
+    ).toThrow('Invalid BindingMemoryState JSON')
+  })
+
   /**
 diff --git a/src/inference.rs b/src/inference.rs
 index f5b20bf..6ff1d3b 100644
@@ -0,0 +1,82 @@
+import * as fs from 'node:fs'
+import {
+  computeOptimalSteps,
+  computeReviewCosts,
+  convertCsvToFsrsItemEntries,
+  convertCsvToFsrsItems,
+  FSRSBindingRevlog,
+} from '@open-spaced-repetition/binding'
+
+describe('FSRSBindingRevlog', () => {
+  const csvBuffer = fs.readFileSync(new URL('./revlog.csv', import.meta.url))
+  const nextDayStartsAt = 4
+  const timezone = 'Asia/Shanghai'
+
+  test('matches the one-shot functions', () => {
+    const revlog = new FSRSBindingRevlog(csvBuffer)
+
+    expect(
+      revlog
+        .toFsrsItems(nextDayStartsAt, timezone)
+        .map((item) => item.toString())
+    ).toEqual(
+      convertCsvToFsrsItems(csvBuffer, nextDayStartsAt, timezone).map((item) =>
+        item.toString()
+      )
+    )
+    expect(
+      revlog
+        .toFsrsItemEntries(nextDayStartsAt, timezone)
+        .map((entry) => [entry.cardId, entry.reviewTime, entry.rowIndex])
+    ).toEqual(
+      convertCsvToFsrsItemEntries(csvBuffer, nextDayStartsAt, timezone).map(
+        (entry) => [entry.cardId, entry.reviewTime, entry.rowIndex]
+      )
+    )
+    expect(revlog.computeOptimalSteps(0.9, 0.5)).toEqual(
+      computeOptimalSteps(csvBuffer, 0.9, 0.5)
+    )
+    expect(revlog.computeReviewCosts()).toEqual(computeReviewCosts(csvBuffer))
+  })
+
+  test('stats', () => {
+    const rows = csvBuffer
+      .toString('utf8')
+      .trim()
+      .split(/\r?\n/)
+      .slice(1)
+      .map((row) => row.split(','))
+    const revlog = new FSRSBindingRevlog(csvBuffer)
+    const stats = revlog.stats()
+
+    expect(revlog.length).toBe(rows.length)
+    expect(stats.reviewCount).toBe(rows.length)
+    expect(stats.cardCount).toBe(new Set(rows.map((row) => row[0])).size)
+    expect(stats.firstReviewTime).toBe(
+      Math.min(...rows.map((row) => Number(row[1])))
+    )
+    expect(stats.ratingCounts.length).toBe(4)
+    expect(stats.ratingCounts.reduce((a, b) => a + b, 0)).toBeLessThanOrEqual(
+      rows.length
+    )
+  })
+
+  test('keeps the rows skipped in lenient mode', () => {
+    const revlog = new FSRSBindingRevlog(
+      Buffer.from(
+        [
+          'card_id,review_time,review_rating,review_state,review_duration',
+          '1,1700000000000,3,1,1000',
+          '1,oops,3,2,1000',
+        ].join('\n')
+      ),
+      { lenient: true }
+    )
+    expect(revlog.length).toBe(1)
+    expect(revlog.skippedRows).toEqual([
+      expect.objectContaining({ line: 3, column: 'review_time', value: 'oops' }),
+    ])
+    expect(revlog.computeOptimalSteps(0.9, 0.5).skippedRows.length).toBe(1)
+    expect(revlog.stats().firstReviewTime).toBe(1700000000000)
+  })
+})
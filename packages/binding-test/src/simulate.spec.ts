@@ -0,0 +1,185 @@
+import {
+  computeOptimalRetention,
+  computeReviewCosts,
+  computeWorkloadCurve,
+  simulate,
+} from '@open-spaced-repetition/binding'
+
+describe('simulate', () => {
+  const config = {
+    deckSize: 1000,
+    learnSpan: 60,
+    learnLimit: 20,
+    reviewLimit: 200,
+    desiredRetention: 0.9,
+    seed: 42,
+  }
+
+  test('returns per-day statistics for the whole span', async () => {
+    const result = await simulate(null, config)
+
+    expect(result.memorizedCntPerDay.length).toBe(config.learnSpan)
+    expect(result.reviewCntPerDay.length).toBe(config.learnSpan)
+    expect(result.learnCntPerDay.length).toBe(config.learnSpan)
+    expect(result.costPerDay.length).toBe(config.learnSpan)
+
+    for (const learned of result.learnCntPerDay) {
+      expect(learned).toBeLessThanOrEqual(config.learnLimit)
+    }
+    const totalLearned = result.learnCntPerDay.reduce((a, b) => a + b, 0)
+    expect(totalLearned).toBeLessThanOrEqual(config.deckSize)
+    expect(result.memorizedCntPerDay.at(-1)).toBeGreaterThan(0)
+  })
+
+  test('higher retention costs more reviews', async () => {
+    const low = await simulate(null, { ...config, desiredRetention: 0.8 })
+    const high = await simulate(null, { ...config, desiredRetention: 0.95 })
+    const sum = (xs: number[]) => xs.reduce((a, b) => a + b, 0)
+    expect(sum(high.reviewCntPerDay)).toBeGreaterThan(sum(low.reviewCntPerDay))
+  })
+
+  test('rejects malformed review costs', () => {
+    expect(() => simulate(null, { ...config, reviewCosts: [1, 2, 3] })).toThrow(
+      'reviewCosts must have exactly 4 elements'
+    )
+  })
+
+  test('computeOptimalRetention suggests a retention within range', async () => {
+    let calls = 0
+    const retention = await computeOptimalRetention(null, config, {
+      progress: (current: number, total: number) => {
+        calls++
+        console.debug(`[cmrr] Progress: ${current}/${total}`)
+      },
+      timeout: 10,
+    })
+    expect(retention).toBeGreaterThanOrEqual(0.7)
+    expect(retention).toBeLessThanOrEqual(0.95)
+    expect(calls).toBeGreaterThan(0)
+  }, 60_000)
+
+  test('computeOptimalRetention searches the requested range', async () => {
+    const retention = await computeOptimalRetention(null, config, {
+      minRetention: 0.85,
+      maxRetention: 0.9,
+      samples: 2,
+    })
+    expect(retention).toBeGreaterThanOrEqual(0.85)
+    expect(retention).toBeLessThanOrEqual(0.9)
+
+    expect(() =>
+      computeOptimalRetention(null, config, { minRetention: 0.9, maxRetention: 0.8 })
+    ).toThrow('retention range')
+    expect(() => computeOptimalRetention(null, config, { samples: 0 })).toThrow(
+      'samples must be at least 1'
+    )
+  }, 60_000)
+
+  test('returning false aborts computeOptimalRetention', async () => {
+    const result = computeOptimalRetention(null, config, {
+      progress: () => false,
+      timeout: 10,
+    })
+    await expect(result).rejects.toThrow('aborted')
+  }, 60_000)
+
+  test('computeReviewCosts extracts costs and rating probabilities', async () => {
+    const csv = Buffer.from(
+      [
+        'card_id,review_time,review_rating,review_state,review_duration',
+        // card 1: learned with Good, then reviewed with Good and Again
+        '1,0,3,0,8000',
+        '1,86400000,3,2,4000',
+        '1,259200000,1,2,12000',
+        '1,259260000,3,3,6000',
+        // card 2: learned with Again then Good, then reviewed with Easy
+        '2,0,1,0,10000',
+        '2,60000,3,1,5000',
+        '2,86400000,4,2,2000',
+      ].join('\n')
+    )
+    const costs = computeReviewCosts(csv)
+
+    expect(costs.firstRatingProb).toEqual([0.5, 0, 0.5, 0])
+    expect(costs.reviewRatingProb).toEqual([0, 0.5, 0.5])
+    expect(costs.learnCosts[0]).toBeCloseTo(10)
+    expect(costs.learnCosts[2]).toBeCloseTo(6.5)
+    expect(costs.reviewCosts[0]).toBeCloseTo(12)
+    expect(costs.reviewCosts[2]).toBeCloseTo(4)
+    expect(costs.reviewCosts[3]).toBeCloseTo(2)
+    expect(costs.relearnCosts[2]).toBeCloseTo(6)
+
+    // the result plugs straight into the simulator config
+    const result = await simulate(null, { ...config, ...costs })
+    expect(result.costPerDay.length).toBe(config.learnSpan)
+  })
+
+  test('computeReviewCosts accepts entries and CSV parse options', () => {
+    const rows = [
+      [1, 0, 3, 0, 8000],
+      [1, 86400000, 3, 2, 4000],
+      [2, 0, 1, 0, 10000],
+      [2, 60000, 3, 1, 5000],
+    ]
+    const entries = rows.map(
+      ([cardId, reviewTime, reviewRating, reviewState, reviewDuration]) => ({
+        cardId,
+        reviewTime,
+        reviewRating,
+        reviewState,
+        reviewDuration,
+      })
+    )
+    const csv = (delimiter: string, extra: string[]) =>
+      Buffer.from(
+        [
+          'card_id,review_time,review_rating,review_state,review_duration',
+          ...rows.map((row) => row.join(',')),
+          ...extra,
+        ]
+          .join('\n')
+          .replaceAll(',', delimiter)
+      )
+
+    const expected = computeReviewCosts(csv(',', []))
+    expect(computeReviewCosts(entries)).toEqual(expected)
+    expect(
+      computeReviewCosts(csv(';', ['3;0;five;0;1000']), {
+        lenient: true,
+        schema: { delimiter: ';' },
+      })
+    ).toEqual(expected)
+    expect(() => computeReviewCosts(entries, { lenient: true })).toThrow(
+      'lenient and schema only apply to CSV input'
+    )
+  })
+
+  test('computeWorkloadCurve sweeps desired retention', async () => {
+    let calls = 0
+    const curve = await computeWorkloadCurve(null, config, {
+      minRetention: 0.7,
+      maxRetention: 0.95,
+      step: 0.05,
+      progress: () => {
+        calls++
+      },
+      timeout: 10,
+    })
+
+    expect(curve.map((p) => p.desiredRetention)).toEqual([
+      0.7, 0.75, 0.8, 0.85, 0.9, 0.95,
+    ].map((r) => expect.closeTo(r, 6)))
+    expect(curve.at(-1)!.reviewCount).toBeGreaterThan(curve[0].reviewCount)
+    expect(curve.at(-1)!.memorized).toBeGreaterThan(curve[0].memorized)
+    expect(calls).toBeGreaterThan(0)
+
+    expect(() =>
+      computeWorkloadCurve(null, config, { minRetention: 0.9, maxRetention: 0.8 })
+    ).toThrow()
+    for (const step of [1e-300, 1e-6]) {
+      expect(() => computeWorkloadCurve(null, config, { step })).toThrow(
+        'step is too small'
+      )
+    }
+  }, 60_000)
+})
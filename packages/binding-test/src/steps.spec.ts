@@ -44,6 +44,39 @@ describe('computeOptimalSteps', () => {
       console.debug('Step stats result:', JSON.stringify(result, null, 2))
     })
 
+    test('should accept a custom csv schema', () => {
+      const [header, ...rows] = csvBuffer.toString('utf8').trim().split(/\r?\n/)
+      const renamed = Buffer.from(
+        [header.replace('card_id', 'id'), ...rows]
+          .map((line) => line.replaceAll(',', ';'))
+          .join('\n')
+      )
+
+      const result = computeOptimalSteps(renamed, 0.9, 0.5, {
+        schema: { columns: { cardId: 'id' }, delimiter: ';' },
+      })
+      expect(result).toEqual(computeOptimalSteps(csvBuffer, 0.9, 0.5))
+    })
+
+    test('should accept revlog entries instead of csv', () => {
+      const [, ...rows] = csvBuffer.toString('utf8').trim().split(/\r?\n/)
+      const entries = rows.map((row) => {
+        const [cardId, reviewTime, reviewRating, reviewState, reviewDuration] =
+          row.split(',').map(Number)
+        return {
+          cardId,
+          reviewTime,
+          reviewRating,
+          reviewState,
+          reviewDuration,
+        }
+      })
+
+      expect(computeOptimalSteps(entries, 0.9, 0.5)).toEqual(
+        computeOptimalSteps(csvBuffer, 0.9, 0.5)
+      )
+    })
+
     test('should compute step stats with parameters array', () => {
       const params = [...defaultParams]
       const result = computeOptimalSteps(csvBuffer, 0.9, params)
@@ -143,9 +176,26 @@ describe('computeOptimalSteps', () => {
       const malformedCsv = buildCsvBuffer(['1,not-a-time,1,0,0'])
 
       expect(() => computeOptimalSteps(malformedCsv, 0.9, 0.5)).toThrow(
-        'CSV deserialization error'
+        'CSV deserialization error at line 2, column review_time'
       )
     })
+
+    test('should skip malformed csv rows in lenient mode', () => {
+      const csv = buildCsvBuffer([
+        '1,0,1,0,0',
+        '1,not-a-time,1,0,0',
+        '1,60000,3,1,0',
+      ])
+
+      const result = computeOptimalSteps(csv, 0.9, 0.5, { lenient: true })
+      expect(result.skippedRows).toEqual([
+        expect.objectContaining({
+          line: 3,
+          column: 'review_time',
+          value: 'not-a-time',
+        }),
+      ])
+    })
   })
 
   describe('boundary behavior', () => {
@@ -5,6 +5,7 @@ import {
   evaluateWithTimeSeriesSplits,
   FSRSBindingItem,
   FSRSBindingReview,
+  type ProgressEvent,
 } from '@open-spaced-repetition/binding'
 import { getTimezoneOffset } from './helpers/csv-parser.js'
 
@@ -118,6 +119,138 @@ describe('FSRS compute_parameters', () => {
     expect(callCount).toBeGreaterThanOrEqual(2)
   })
 
+  test.each([
+    { name: 'computeParameters', fn: computeParameters },
+    {
+      name: 'evaluateWithTimeSeriesSplits',
+      fn: evaluateWithTimeSeriesSplits,
+    },
+  ])('aborting the signal cancels $name', async ({ fn }) => {
+    const controller = new AbortController()
+    const start = Date.now()
+    const result = fn(allItems, {
+      enableShortTerm: true,
+      signal: controller.signal,
+    })
+    setTimeout(() => controller.abort(), 100)
+    await expect(result).rejects.toThrow()
+    expect(Date.now() - start).toBeLessThan(30_000)
+  })
+
+  test('aborting the signal stops the split evaluateWithTimeSeriesSplits is training', async () => {
+    const controller = new AbortController()
+    const events: ProgressEvent[] = []
+    const result = evaluateWithTimeSeriesSplits(allItems, {
+      enableShortTerm: true,
+      signal: controller.signal,
+      onProgressEvent: (event: ProgressEvent) => events.push(event),
+    })
+    setTimeout(() => controller.abort(), 100)
+    await expect(result).rejects.toThrow()
+    // an event is only sent once a split is done
+    expect(events).toEqual([])
+  })
+
+  test.each([
+    { name: 'computeParameters', fn: computeParameters },
+    {
+      name: 'evaluateWithTimeSeriesSplits',
+      fn: evaluateWithTimeSeriesSplits,
+    },
+  ])('deadlineMs rejects $name with a DeadlineExceeded error', async ({
+    fn,
+  }) => {
+    // a deadline of 0 ms has always passed when the computation starts
+    const result = fn(allItems, { enableShortTerm: true, deadlineMs: 0 })
+    await expect(result).rejects.toThrow(/^DeadlineExceeded/)
+  })
+
+  test.each([
+    { name: 'computeParameters', fn: computeParameters, phase: 'train' },
+    {
+      name: 'evaluateWithTimeSeriesSplits',
+      fn: evaluateWithTimeSeriesSplits,
+      phase: 'evaluate',
+    },
+  ])('deadlineMs stops $name while it trains', async ({ fn, phase }) => {
+    const events: ProgressEvent[] = []
+    const start = Date.now()
+    const result = fn(allItems, {
+      enableShortTerm: true,
+      deadlineMs: 200,
+      timeout: 50,
+      onProgressEvent: (event: ProgressEvent) => events.push(event),
+    })
+    await expect(result).rejects.toThrow(/^DeadlineExceeded/)
+    expect(Date.now() - start).toBeLessThan(30_000)
+    // the deadline passed before the computation got to its end
+    expect(
+      events.some(
+        (event) => event.phase === phase && event.current === event.total
+      )
+    ).toBe(false)
+  })
+
+  test.each([
+    { name: 'computeParameters', fn: computeParameters, phase: 'train' },
+    {
+      name: 'evaluateWithTimeSeriesSplits',
+      fn: evaluateWithTimeSeriesSplits,
+      phase: 'evaluate',
+    },
+  ])('onProgressEvent reports $phase events for $name', async ({
+    fn,
+    phase,
+  }) => {
+    const events: ProgressEvent[] = []
+    await fn(allItems, {
+      enableShortTerm: true,
+      onProgressEvent: (event: ProgressEvent) => events.push(event),
+    })
+
+    const phaseEvents = events.filter((event) => event.phase === phase)
+    expect(phaseEvents.length).toBeGreaterThan(0)
+    for (const event of phaseEvents) {
+      expect(event.current).toBeLessThanOrEqual(event.total)
+      expect(event.split).toBeLessThanOrEqual(event.splitTotal ?? 0)
+    }
+    if (phase === 'train') {
+      expect(phaseEvents[0].epoch).toBeDefined()
+      expect(phaseEvents[0].epochTotal).toBeGreaterThan(0)
+    }
+    expect(phaseEvents.some((event) => event.etaMs !== undefined)).toBe(true)
+
+    // events arrive in order: phases never go back, and neither do the split,
+    // epoch and progress within a phase
+    const phases = ['pretrain', 'train', 'evaluate']
+    const position = (event: ProgressEvent) => [
+      phases.indexOf(event.phase),
+      event.split ?? -1,
+      event.epoch ?? -1,
+      event.current,
+    ]
+    for (let i = 1; i < events.length; i++) {
+      const [previous, next] = [position(events[i - 1]), position(events[i])]
+      const changed = next.findIndex((value, j) => value !== previous[j])
+      if (changed !== -1) {
+        expect(next[changed]).toBeGreaterThan(previous[changed])
+      }
+    }
+
+    // the last event reports completion; only an evaluation has a loss
+    const last = events[events.length - 1]
+    expect(last.phase).toBe(phase)
+    expect(last.current).toBe(last.total)
+    if (phase === 'evaluate') {
+      expect(last.loss).toBeGreaterThan(0)
+      expect(Number.isFinite(last.loss)).toBe(true)
+    } else {
+      expect(last.loss).toBeUndefined()
+    }
+    const earlier = events.slice(0, -1)
+    expect(earlier.every((event) => event.loss === undefined)).toBe(true)
+  }, 180_000)
+
   test.each([
     { name: 'computeParameters', fn: computeParameters },
     {
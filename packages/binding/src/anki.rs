@@ -0,0 +1,190 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use fsrs_binding_core::{RevlogEntry, STATE_LEARNING, STATE_RELEARNING, STATE_REVIEW};
+use napi::bindgen_prelude::{FnArgs, Function, Result};
+use napi_derive::napi;
+use rusqlite::{Connection, OpenFlags};
+
+use crate::FSRSItem as FSRSBindingItem;
+use crate::convert::{OffsetProvider, convert_revlogs_to_fsrs_items};
+
+// Anki revlog types
+const REVLOG_LEARN: u8 = 0;
+const REVLOG_REVIEW: u8 = 1;
+const REVLOG_RELEARN: u8 = 2;
+const REVLOG_FILTERED: u8 = 3;
+const REVLOG_MANUAL: u8 = 4;
+
+/// Collection files inside a .colpkg/.apkg archive, newest format first.
+/// `collection.anki21b` is zstd-compressed.
+const ARCHIVE_COLLECTIONS: [&str; 3] = [
+  "collection.anki21b",
+  "collection.anki21",
+  "collection.anki2",
+];
+
+/// Removes the extracted collection when dropped
+struct TempCollection(PathBuf);
+
+impl Drop for TempCollection {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.0);
+  }
+}
+
+fn extract_collection(archive_path: &Path) -> Result<TempCollection> {
+  let file = File::open(archive_path)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to open Anki archive: {}", e)))?;
+  let mut archive = zip::ZipArchive::new(file)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read Anki archive: {}", e)))?;
+
+  let name = ARCHIVE_COLLECTIONS
+    .into_iter()
+    .find(|name| archive.index_for_name(name).is_some())
+    .ok_or_else(|| napi::Error::from_reason("No collection found in Anki archive".to_string()))?;
+  let mut entry = archive
+    .by_name(name)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read Anki archive: {}", e)))?;
+  let mut data = Vec::new();
+  entry
+    .read_to_end(&mut data)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read Anki archive: {}", e)))?;
+  if name.ends_with(".anki21b") {
+    data = zstd::decode_all(data.as_slice()).map_err(|e| {
+      napi::Error::from_reason(format!("Failed to decompress Anki collection: {}", e))
+    })?;
+  }
+
+  let nanos = std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .map(|d| d.as_nanos())
+    .unwrap_or_default();
+  let path = std::env::temp_dir().join(format!(
+    "fsrs-binding-{}-{}.anki2",
+    std::process::id(),
+    nanos
+  ));
+  let collection = TempCollection(path);
+  File::create(&collection.0)
+    .and_then(|mut f| f.write_all(&data))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to extract Anki collection: {}", e)))?;
+  Ok(collection)
+}
+
+/// An Anki revlog row, classified the way Anki does for FSRS
+enum AnkiRevlogRow {
+  Review(RevlogEntry),
+  /// "Forget"/reset: a manual entry with a zero ease factor.
+  /// The card's earlier history no longer applies.
+  Reset,
+  /// Manual and rescheduled entries, and cram reviews in filtered decks with
+  /// rescheduling disabled (recorded with a zero ease factor)
+  Skipped,
+}
+
+fn revlog_entry_from_anki(
+  id: i64,
+  card_id: i64,
+  ease: u32,
+  kind: u8,
+  duration: u32,
+  factor: u32,
+) -> AnkiRevlogRow {
+  if kind == REVLOG_MANUAL && factor == 0 {
+    return AnkiRevlogRow::Reset;
+  }
+  if !(1..=4).contains(&ease) {
+    return AnkiRevlogRow::Skipped;
+  }
+  let review_state = match kind {
+    REVLOG_LEARN => STATE_LEARNING,
+    REVLOG_REVIEW => STATE_REVIEW,
+    REVLOG_RELEARN => STATE_RELEARNING,
+    REVLOG_FILTERED if factor != 0 => STATE_REVIEW,
+    _ => return AnkiRevlogRow::Skipped,
+  };
+  AnkiRevlogRow::Review(RevlogEntry {
+    card_id: card_id.to_string(),
+    review_time: id,
+    review_rating: ease,
+    review_state,
+    review_duration: duration,
+    row_index: 0,
+  })
+}
+
+pub(crate) fn read_anki_revlog(path: &Path) -> Result<Vec<RevlogEntry>> {
+  let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to open Anki collection: {}", e)))?;
+  let mut stmt = conn
+    .prepare("SELECT id, cid, ease, type, time, factor FROM revlog ORDER BY cid, id")
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read Anki revlog: {}", e)))?;
+  let rows = stmt
+    .query_map([], |row| {
+      let card_id: i64 = row.get(1)?;
+      Ok((
+        card_id,
+        revlog_entry_from_anki(
+          row.get(0)?,
+          card_id,
+          row.get(2)?,
+          row.get(3)?,
+          row.get::<_, i64>(4)?.clamp(0, u32::MAX as i64) as u32,
+          row.get::<_, i64>(5)?.max(0) as u32,
+        ),
+      ))
+    })
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read Anki revlog: {}", e)))?;
+
+  let mut revlogs = Vec::new();
+  // row_index refers to the position in the revlog table ordered by (cid, id)
+  for (row_index, row) in rows.enumerate() {
+    let (card_id, row) =
+      row.map_err(|e| napi::Error::from_reason(format!("Failed to read Anki revlog: {}", e)))?;
+    match row {
+      AnkiRevlogRow::Review(mut entry) => {
+        entry.row_index = row_index;
+        revlogs.push(entry);
+      }
+      // rows are ordered by card, so the card's earlier reviews are the last ones read
+      AnkiRevlogRow::Reset => {
+        let card_id = card_id.to_string();
+        while revlogs.last().is_some_and(|entry| entry.card_id == card_id) {
+          revlogs.pop();
+        }
+      }
+      AnkiRevlogRow::Skipped => {}
+    }
+  }
+  Ok(revlogs)
+}
+
+/// Convert the review history of an Anki collection into FSRS items.
+/// `path` may point to a collection file (`collection.anki2`/`.anki21`) or
+/// to a `.colpkg`/`.apkg` archive.
+#[napi]
+pub fn convert_anki_collection_to_fsrs_items(
+  path: String,
+  next_day_starts_at: i64,
+  timezone: String,
+  #[napi(ts_arg_type = "(ms: number, timezone: string) => number")] offset_provider: Option<
+    Function<FnArgs<(i64, String)>, i32>,
+  >,
+) -> Result<Vec<FSRSBindingItem>> {
+  let offset_provider = OffsetProvider::new(&timezone, offset_provider.as_ref())?;
+  let path = Path::new(&path);
+  let is_archive = path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "colpkg" | "apkg"));
+
+  let revlogs = if is_archive {
+    let collection = extract_collection(path)?;
+    read_anki_revlog(&collection.0)?
+  } else {
+    read_anki_revlog(path)?
+  };
+  convert_revlogs_to_fsrs_items(revlogs, next_day_starts_at, &offset_provider)
+}
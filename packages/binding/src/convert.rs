@@ -1,174 +1,503 @@
-use csv::ReaderBuilder;
-use fsrs::filter_outlier;
+use fsrs_binding_core::OffsetProvider as _;
+use fsrs_binding_core::RevlogEntry;
 use itertools::Itertools;
 use napi_derive::napi;
 
-use napi::bindgen_prelude::{FnArgs, Function, Result};
-use serde::{Deserialize, Serialize};
-use time::{Date, Duration, OffsetDateTime};
+use napi::bindgen_prelude::{AsyncTask, Buffer, Either, Either3, Env, FnArgs, Result, Task};
+use std::sync::{Arc, Mutex};
 
 use crate::FSRSItem as FSRSBindingItem;
+use crate::progress::{self, ProgressState};
+use crate::{
+  ConvertCsvOptions, ConvertCsvReport, ConvertOptions, CsvParseOptions, CsvRowError, CsvSchema,
+  FSRSItemEntry, OffsetFunc, RevlogColumns, RevlogInputEntry,
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct RevlogEntry {
-  // card_id,review_time,review_rating,review_state,review_duration
-  pub card_id: String,
-  pub review_time: i64,
-  pub review_rating: u32,
-  pub review_state: u32,
-  pub review_duration: u32,
-  #[serde(skip)]
-  pub last_interval: i32,
+/// Surface an error from the core crate to JS
+pub(crate) fn core_error(e: fsrs_binding_core::Error) -> napi::Error {
+  napi::Error::from_reason(e.to_string())
 }
 
-fn convert_to_date(
-  timestamp: i64,
-  next_day_starts_at: i64,
-  timezone: &str,
-  offset_provider: &Function<FnArgs<(i64, String)>, i32>,
-) -> Result<Date> {
-  let timestamp_secs = timestamp / 1000;
-  let dt = OffsetDateTime::from_unix_timestamp(timestamp_secs)
-    .map_err(|e| napi::Error::from_reason(format!("Invalid timestamp: {}", e)))?;
-
-  // Compute offset minutes via JS callback if provided; fall back to fixed +8h.
-  let offset_minutes: i64 = offset_provider
-    .call(FnArgs {
-      data: (timestamp, timezone.to_string()),
-    })?
-    .into();
-  let adjusted_dt = dt + Duration::minutes(offset_minutes) - Duration::hours(next_day_starts_at);
-  Ok(adjusted_dt.date())
-}
-
-fn remove_revlog_before_last_first_learn(entries: Vec<RevlogEntry>) -> Vec<RevlogEntry> {
-  // 0 new, 1 learning, 2 review, 3 relearning
-  // Keep only entries from the last contiguous block of learning states (0 or 1)
-  let is_learning_state = |entry: &RevlogEntry| matches!(entry.review_state, 0 | 1);
-
-  let mut last_learning_block_start = None;
-  for i in (0..entries.len()).rev() {
-    if is_learning_state(&entries[i]) {
-      last_learning_block_start = Some(i);
-    } else if last_learning_block_start.is_some() {
-      break;
+/// Apply the defaults to a `CsvSchema` and validate it
+pub(crate) fn csv_schema(schema: Option<&CsvSchema>) -> Result<fsrs_binding_core::CsvSchema> {
+  let mut format = fsrs_binding_core::CsvSchema::default();
+  let Some(schema) = schema else {
+    return Ok(format);
+  };
+
+  if let Some(mapping) = &schema.columns {
+    let columns = &mut format.columns;
+    for (column, name) in [
+      (&mut columns.card_id, &mapping.card_id),
+      (&mut columns.review_time, &mapping.review_time),
+      (&mut columns.review_rating, &mapping.review_rating),
+      (&mut columns.review_state, &mapping.review_state),
+      (&mut columns.review_duration, &mapping.review_duration),
+    ] {
+      if let Some(name) = name {
+        column.clone_from(name);
+      }
     }
   }
 
-  if let Some(start) = last_learning_block_start {
-    entries[start..].to_vec()
-  } else {
-    vec![]
+  match schema.delimiter.as_deref() {
+    None => {}
+    Some(d) if d.len() == 1 && d.is_ascii() => format.delimiter = d.as_bytes()[0],
+    Some(d) => {
+      return Err(napi::Error::from_reason(format!(
+        "delimiter must be a single ASCII character, got {d:?}"
+      )));
+    }
   }
+
+  if let Some(unit) = schema.timestamp_unit.as_deref() {
+    format.timestamp_unit = unit.parse().map_err(core_error)?;
+  }
+
+  Ok(format)
 }
 
-fn convert_to_fsrs_items_internal(
-  mut entries: Vec<RevlogEntry>,
-  next_day_starts_at: i64,
-  timezone: &str,
-  offset_provider: &Function<FnArgs<(i64, String)>, i32>,
-) -> Result<Vec<(String, FSRSBindingItem, i64)>> {
-  entries = remove_revlog_before_last_first_learn(entries);
-
-  if !entries.is_empty() {
-    let mut prev_date = convert_to_date(
-      entries[0].review_time,
-      next_day_starts_at,
-      timezone,
-      offset_provider,
-    )?;
-    for item in entries.iter_mut().skip(1) {
-      let date_current = convert_to_date(
-        item.review_time,
-        next_day_starts_at,
-        timezone,
-        offset_provider,
-      )?;
-      item.last_interval = (date_current - prev_date).whole_days() as i32;
-      prev_date = date_current;
-    }
+/// Parse revlog entries from CSV, by default with the header
+/// `card_id,review_time,review_rating,review_state,review_duration`.
+/// Malformed rows fail the parse, or are skipped and returned when `lenient` is set.
+fn read_revlog_csv(
+  data: &[u8],
+  options: Option<&CsvParseOptions>,
+) -> Result<(Vec<RevlogEntry>, Vec<CsvRowError>)> {
+  let format = csv_schema(options.and_then(|x| x.schema.as_ref()))?;
+  let lenient = options.and_then(|x| x.lenient).unwrap_or(false);
+  let (revlogs, skipped_rows) =
+    fsrs_binding_core::read_revlog_csv(data, &format, lenient).map_err(core_error)?;
+  Ok((
+    revlogs,
+    skipped_rows.into_iter().map(CsvRowError::from).collect(),
+  ))
+}
+
+/// Revlog data accepted by the conversion and analysis functions: CSV bytes,
+/// an array of entries, or columnar typed arrays
+pub(crate) type RevlogInput = Either3<Buffer, Vec<RevlogInputEntry>, RevlogColumns>;
+
+/// Parse options only apply to CSV; reject them for typed input instead of ignoring them
+fn check_parse_options(
+  data: &RevlogInput,
+  lenient: Option<bool>,
+  schema: Option<&CsvSchema>,
+) -> Result<()> {
+  if !matches!(data, Either3::A(_)) && (lenient.is_some() || schema.is_some()) {
+    return Err(napi::Error::from_reason(
+      "lenient and schema only apply to CSV input".to_string(),
+    ));
   }
+  Ok(())
+}
 
-  Ok(
-    entries
+/// Read revlog entries from any `RevlogInput`. Parse options only apply to CSV.
+pub(crate) fn read_revlog_input(
+  data: &RevlogInput,
+  options: Option<&CsvParseOptions>,
+) -> Result<(Vec<RevlogEntry>, Vec<CsvRowError>)> {
+  check_parse_options(
+    data,
+    options.and_then(|x| x.lenient),
+    options.and_then(|x| x.schema.as_ref()),
+  )?;
+  let revlogs = match data {
+    Either3::A(csv) => return read_revlog_csv(csv, options),
+    Either3::B(entries) => entries
       .iter()
       .enumerate()
-      .skip(1)
-      .map(|(idx, entry)| {
-        let reviews = entries
-          .iter()
-          .take(idx + 1)
-          .map(|r| fsrs::FSRSReview {
-            rating: r.review_rating,
-            delta_t: r.last_interval.max(0) as u32,
-          })
-          .collect();
-        (
-          entry.card_id.clone(),
-          FSRSBindingItem {
-            inner: fsrs::FSRSItem { reviews },
-          },
-          entry.review_time,
-        )
+      .map(|(row_index, entry)| RevlogEntry {
+        card_id: match &entry.card_id {
+          Either::A(id) => id.clone(),
+          Either::B(id) => id.to_string(),
+        },
+        review_time: entry.review_time,
+        review_rating: entry.review_rating,
+        review_state: entry.review_state,
+        review_duration: entry.review_duration,
+        row_index,
       })
-      .filter(|(_, item, _)| item.current().is_some_and(|r| r.inner.delta_t > 0))
       .collect(),
-  )
+    Either3::C(columns) => {
+      let len = columns.card_ids.len();
+      if [
+        columns.review_times.len(),
+        columns.review_ratings.len(),
+        columns.review_states.len(),
+        columns.review_durations.len(),
+      ]
+      .iter()
+      .any(|&l| l != len)
+      {
+        return Err(napi::Error::from_reason(
+          "revlog columns must have the same length".to_string(),
+        ));
+      }
+      (0..len)
+        .map(|row_index| RevlogEntry {
+          card_id: columns.card_ids[row_index].to_string(),
+          review_time: columns.review_times[row_index],
+          review_rating: columns.review_ratings[row_index] as u32,
+          review_state: columns.review_states[row_index] as u32,
+          review_duration: columns.review_durations[row_index],
+          row_index,
+        })
+        .collect()
+    }
+  };
+  Ok((revlogs, Vec::new()))
+}
+
+/// Resolves the UTC offset of a review time, natively from the embedded tz database
+/// or through a JS callback that overrides it.
+pub(crate) enum OffsetProvider<'a> {
+  TimeZone(jiff::tz::TimeZone),
+  Callback {
+    timezone: &'a str,
+    callback: &'a OffsetFunc<'a>,
+  },
+}
+
+impl<'a> OffsetProvider<'a> {
+  pub(crate) fn new(timezone: &'a str, callback: Option<&'a OffsetFunc<'a>>) -> Result<Self> {
+    match callback {
+      Some(callback) => Ok(Self::Callback { timezone, callback }),
+      None => fsrs_binding_core::time_zone(timezone)
+        .map(Self::TimeZone)
+        .map_err(core_error),
+    }
+  }
 }
 
+impl fsrs_binding_core::OffsetProvider for OffsetProvider<'_> {
+  fn offset_seconds(&self, timestamp: i64) -> fsrs_binding_core::Result<i64> {
+    match self {
+      Self::TimeZone(tz) => tz.offset_seconds(timestamp),
+      Self::Callback { timezone, callback } => {
+        let offset_minutes: i64 = callback
+          .call(FnArgs {
+            data: (timestamp, timezone.to_string()),
+          })
+          .map_err(|e| fsrs_binding_core::Error::Offset(e.reason))?
+          .into();
+        Ok(offset_minutes * 60)
+      }
+    }
+  }
+}
+
+/// Last argument of the synchronous conversions: an offset provider, or options
+/// that may include one
+pub(crate) type ConvertArg<'a> = Either<OffsetFunc<'a>, ConvertOptions<'a>>;
+
+fn split_convert_arg<'a>(
+  arg: &'a Option<ConvertArg<'a>>,
+) -> (Option<&'a OffsetFunc<'a>>, Option<CsvParseOptions>) {
+  match arg {
+    None => (None, None),
+    Some(Either::A(callback)) => (Some(callback), None),
+    Some(Either::B(options)) => (
+      options.offset_provider.as_ref(),
+      Some(options.parse_options()),
+    ),
+  }
+}
+
+/// Convert a revlog into FSRS items. `data` is a CSV export, an array of
+/// entries or columnar typed arrays.
+/// Day boundaries are computed in the IANA `timezone` (DST-aware, from an embedded
+/// tz database). An offset provider, passed directly or as `options.offsetProvider`,
+/// overrides it and returns the UTC offset in minutes for a review time.
 #[napi]
 pub fn convert_csv_to_fsrs_items(
-  data: &[u8],
+  #[napi(ts_arg_type = "Buffer | FSRSBindingRevlogEntry[] | FSRSBindingRevlogColumns")]
+  data: RevlogInput,
   next_day_starts_at: i64,
   timezone: String,
-  #[napi(ts_arg_type = "(ms: number, timezone: string) => number")] offset_provider: Function<
-    FnArgs<(i64, String)>,
-    i32,
-  >,
+  #[napi(ts_arg_type = "((ms: number, timezone: string) => number) | ConvertOptions")]
+  options: Option<ConvertArg>,
 ) -> Result<Vec<FSRSBindingItem>> {
-  let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(data);
-
-  let mut revlogs: Vec<RevlogEntry> = rdr
-    .deserialize::<RevlogEntry>()
-    .collect::<std::result::Result<Vec<RevlogEntry>, _>>()
-    .map_err(|e| napi::Error::from_reason(format!("CSV deserialization error: {}", e)))?;
-
-  // Sort by review_time first to ensure ordering
-  revlogs.sort_by_cached_key(|r| (r.card_id.clone(), r.review_time));
-
-  // Group by card_id while maintaining time order
-  let mut revlogs = revlogs
-    .into_iter()
-    .chunk_by(|r| r.card_id.clone())
-    .into_iter()
-    .map(|(_card_id, entries)| {
-      convert_to_fsrs_items_internal(
-        entries.collect(),
-        next_day_starts_at,
-        &timezone,
-        &offset_provider,
-      )
-    })
-    .collect::<Result<Vec<_>>>()?
-    .into_iter()
-    .flatten()
-    .collect_vec();
+  let (offset_provider, options) = split_convert_arg(&options);
+  let offset_provider = OffsetProvider::new(&timezone, offset_provider)?;
+  let (revlogs, _) = read_revlog_input(&data, options.as_ref())?;
+  convert_revlogs_to_fsrs_items(revlogs, next_day_starts_at, &offset_provider)
+}
 
-  // Sort by review_time to maintain correct order across groups
-  revlogs.sort_by_cached_key(|(_, _, review_time)| *review_time);
+/// Like `convertCsvToFsrsItems`, additionally reporting the rows skipped in lenient mode.
+#[napi]
+pub fn convert_csv_to_fsrs_items_with_report(
+  #[napi(ts_arg_type = "Buffer | FSRSBindingRevlogEntry[] | FSRSBindingRevlogColumns")]
+  data: RevlogInput,
+  next_day_starts_at: i64,
+  timezone: String,
+  #[napi(ts_arg_type = "((ms: number, timezone: string) => number) | ConvertOptions")]
+  options: Option<ConvertArg>,
+) -> Result<ConvertCsvReport> {
+  let (offset_provider, options) = split_convert_arg(&options);
+  let offset_provider = OffsetProvider::new(&timezone, offset_provider)?;
+  let (revlogs, skipped_rows) = read_revlog_input(&data, options.as_ref())?;
+  Ok(ConvertCsvReport {
+    items: convert_revlogs_to_fsrs_items(revlogs, next_day_starts_at, &offset_provider)?,
+    skipped_rows,
+  })
+}
 
-  Ok(revlogs.into_iter().map(|(_, item, _)| item).collect())
+/// Like `convertCsvToFsrsItems`, but keeps the card id, review time and source
+/// row index of every item, so results can be mapped back to the original cards.
+#[napi]
+pub fn convert_csv_to_fsrs_item_entries(
+  #[napi(ts_arg_type = "Buffer | FSRSBindingRevlogEntry[] | FSRSBindingRevlogColumns")]
+  data: RevlogInput,
+  next_day_starts_at: i64,
+  timezone: String,
+  #[napi(ts_arg_type = "((ms: number, timezone: string) => number) | ConvertOptions")]
+  options: Option<ConvertArg>,
+) -> Result<Vec<FSRSItemEntry>> {
+  let (offset_provider, options) = split_convert_arg(&options);
+  let offset_provider = OffsetProvider::new(&timezone, offset_provider)?;
+  let (revlogs, _) = read_revlog_input(&data, options.as_ref())?;
+  convert_revlogs_to_fsrs_entries(revlogs, next_day_starts_at, &offset_provider)
 }
 
-pub(crate) fn prepare_items(train_set: Vec<&FSRSBindingItem>) -> Vec<fsrs::FSRSItem> {
-  let train_data: Vec<fsrs::FSRSItem> = train_set
-    .into_iter()
-    .map(|item| item.inner.clone())
-    .collect();
-  let (mut dataset_for_initialization, mut trainset): (Vec<fsrs::FSRSItem>, Vec<fsrs::FSRSItem>) =
-    train_data
+/// Group revlog entries by card and turn each card's history into FSRS items,
+/// ordered by review time across cards.
+pub(crate) fn convert_revlogs_to_fsrs_items(
+  revlogs: Vec<RevlogEntry>,
+  next_day_starts_at: i64,
+  offset_provider: &OffsetProvider,
+) -> Result<Vec<FSRSBindingItem>> {
+  let items =
+    fsrs_binding_core::convert_revlogs_to_fsrs_items(revlogs, next_day_starts_at, offset_provider)
+      .map_err(core_error)?;
+  Ok(
+    items
       .into_iter()
-      .partition(|item| item.long_term_review_cnt() == 1);
-  (dataset_for_initialization, trainset) = filter_outlier(dataset_for_initialization, trainset);
-  [dataset_for_initialization, trainset].concat()
+      .map(|inner| FSRSBindingItem { inner })
+      .collect(),
+  )
+}
+
+/// Like `convert_revlogs_to_fsrs_items`, keeping the source of every item
+fn convert_revlogs_to_fsrs_entries(
+  revlogs: Vec<RevlogEntry>,
+  next_day_starts_at: i64,
+  offset_provider: &OffsetProvider,
+) -> Result<Vec<FSRSItemEntry>> {
+  let entries = fsrs_binding_core::convert_revlogs_to_fsrs_entries(
+    revlogs,
+    next_day_starts_at,
+    offset_provider,
+    || true,
+  )
+  .map_err(core_error)?;
+  Ok(entries.into_iter().map(FSRSItemEntry::from).collect())
+}
+
+/// Like `convert_revlogs_to_fsrs_entries`, for entries already ordered by `sort_revlogs`.
+pub(crate) fn convert_sorted_revlogs_to_fsrs_entries(
+  revlogs: &[RevlogEntry],
+  next_day_starts_at: i64,
+  offset_provider: &OffsetProvider,
+) -> Result<Vec<FSRSItemEntry>> {
+  let entries = fsrs_binding_core::convert_sorted_revlogs_to_fsrs_entries(
+    revlogs,
+    next_day_starts_at,
+    offset_provider,
+    || true,
+  )
+  .map_err(core_error)?;
+  Ok(entries.into_iter().map(FSRSItemEntry::from).collect())
+}
+
+/// Rows or cards processed between two updates of the shared progress state
+const PROGRESS_BATCH: usize = 1024;
+
+/// Counts progress locally and publishes it once per `PROGRESS_BATCH` steps,
+/// so the shared state is not locked for every row
+struct BatchedProgress<'a> {
+  state: &'a Mutex<ProgressState>,
+  pending: usize,
+}
+
+impl<'a> BatchedProgress<'a> {
+  fn new(state: &'a Mutex<ProgressState>) -> Self {
+    Self { state, pending: 0 }
+  }
+
+  /// Returns false once an abort was requested
+  fn step(&mut self) -> bool {
+    self.pending += 1;
+    if self.pending < PROGRESS_BATCH {
+      return true;
+    }
+    self.flush()
+  }
+
+  /// Publish the pending steps. Returns false once an abort was requested.
+  fn flush(&mut self) -> bool {
+    let Ok(mut guard) = self.state.lock() else {
+      return true;
+    };
+    guard.current += std::mem::take(&mut self.pending);
+    !guard.want_abort
+  }
+}
+
+pub struct ConvertCsvTask {
+  /// CSV bytes, or entries already read from typed input
+  pub(crate) data: Either<Vec<u8>, Vec<RevlogEntry>>,
+  pub(crate) next_day_starts_at: i64,
+  pub(crate) timezone: jiff::tz::TimeZone,
+  pub(crate) format: fsrs_binding_core::CsvSchema,
+  pub(crate) lenient: bool,
+  pub(crate) state: Arc<Mutex<ProgressState>>,
+  #[cfg(not(target_arch = "wasm32"))]
+  pub(crate) timeout_ms: u32,
+  #[cfg(not(target_arch = "wasm32"))]
+  pub(crate) progress_cb: Option<progress::ProgressCallback>,
+  #[cfg(target_arch = "wasm32")]
+  pub(crate) progress_thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl ConvertCsvTask {
+  /// Progress is counted in rows parsed plus cards converted.
+  fn convert(&mut self) -> Result<Vec<FSRSBindingItem>> {
+    let revlogs = match std::mem::replace(&mut self.data, Either::B(Vec::new())) {
+      Either::A(csv) => {
+        // Rows are not known before parsing; one row per line is a close estimate.
+        if let Ok(mut guard) = self.state.lock() {
+          guard.total = csv.iter().filter(|&&b| b == b'\n').count();
+        }
+        let mut progress = BatchedProgress::new(&self.state);
+        let (revlogs, _) = fsrs_binding_core::read_revlog_csv_with_progress(
+          &csv,
+          &self.format,
+          self.lenient,
+          || progress.step(),
+        )
+        .map_err(core_error)?;
+        progress.flush();
+        revlogs
+      }
+      Either::B(revlogs) => revlogs,
+    };
+
+    let cards = revlogs.iter().map(|r| &r.card_id).unique().count();
+    if let Ok(mut guard) = self.state.lock() {
+      guard.current = revlogs.len();
+      guard.total = revlogs.len() + cards;
+    }
+    let mut progress = BatchedProgress::new(&self.state);
+    let entries = fsrs_binding_core::convert_revlogs_to_fsrs_entries(
+      revlogs,
+      self.next_day_starts_at,
+      &self.timezone,
+      || progress.step(),
+    )
+    .map_err(core_error)?;
+    progress.flush();
+    Ok(
+      entries
+        .into_iter()
+        .map(|entry| FSRSBindingItem { inner: entry.item })
+        .collect(),
+    )
+  }
+}
+
+impl Task for ConvertCsvTask {
+  type Output = Vec<FSRSBindingItem>;
+  type JsValue = Vec<FSRSBindingItem>;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let _progress_thread = {
+      use crate::progress::spawn_progress_poller;
+      spawn_progress_poller(
+        Arc::clone(&self.state),
+        self.timeout_ms,
+        self.progress_cb.take(),
+        None,
+        None,
+      )
+    };
+
+    let result = self.convert();
+
+    if let Ok(mut guard) = self.state.lock() {
+      guard.finished = true;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = _progress_thread.join().ok();
+
+    // WASM: join the progress thread
+    #[cfg(target_arch = "wasm32")]
+    if let Some(handle) = self.progress_thread.take() {
+      let _ = handle.join().ok();
+    }
+
+    result
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+/// Convert a revlog into FSRS items off the main thread. `data` is a CSV export,
+/// an array of entries or columnar typed arrays; typed input is copied before
+/// the task starts.
+/// Day boundaries are computed natively in the IANA `timezone`; use
+/// `convertCsvToFsrsItems` for a custom offset provider.
+#[napi(ts_return_type = "Promise<FSRSBindingItem[]>", catch_unwind)]
+pub fn convert_csv_to_fsrs_items_async(
+  #[napi(ts_arg_type = "Buffer | FSRSBindingRevlogEntry[] | FSRSBindingRevlogColumns")]
+  data: RevlogInput,
+  next_day_starts_at: i64,
+  timezone: String,
+  #[napi(ts_arg_type = "ConvertCsvOptions")] options: Option<ConvertCsvOptions>,
+) -> Result<AsyncTask<ConvertCsvTask>> {
+  let timezone = fsrs_binding_core::time_zone(&timezone).map_err(core_error)?;
+
+  let lenient = options.as_ref().and_then(|x| x.lenient);
+  let schema = options.as_ref().and_then(|x| x.schema.as_ref());
+  check_parse_options(&data, lenient, schema)?;
+  let format = csv_schema(schema)?;
+  let data = match &data {
+    Either3::A(csv) => Either::A(csv.to_vec()),
+    _ => Either::B(read_revlog_input(&data, None)?.0),
+  };
+
+  let state = Arc::new(Mutex::new(ProgressState::default()));
+  let timeout = options.as_ref().and_then(|x| x.timeout).unwrap_or(500);
+
+  let progress_tsfn = options
+    .as_ref()
+    .and_then(|x| x.progress.as_ref())
+    .and_then(|cb| cb.build_threadsafe_function().weak::<true>().build().ok());
+
+  // wasm: start polling here and do not pass callback into task
+  #[cfg(target_arch = "wasm32")]
+  let progress_thread_handle =
+    { progress::spawn_progress_poller(Arc::clone(&state), timeout, progress_tsfn, None, None) };
+  // non-wasm reuses TSFN in task; wasm does not pass callback into task.
+  #[cfg(not(target_arch = "wasm32"))]
+  let progress_tsfn_for_task = progress_tsfn;
+
+  Ok(AsyncTask::new(ConvertCsvTask {
+    data,
+    next_day_starts_at,
+    timezone,
+    format,
+    lenient: lenient.unwrap_or(false),
+    state,
+    #[cfg(not(target_arch = "wasm32"))]
+    timeout_ms: timeout,
+    #[cfg(not(target_arch = "wasm32"))]
+    progress_cb: progress_tsfn_for_task,
+    #[cfg(target_arch = "wasm32")]
+    progress_thread: Some(progress_thread_handle),
+  }))
 }
@@ -0,0 +1,20 @@
+use fsrs_binding_core::{extract_review_costs, sort_revlogs};
+use napi::bindgen_prelude::Result;
+use napi_derive::napi;
+
+use crate::convert::{RevlogInput, read_revlog_input};
+use crate::model::{CsvParseOptions, ReviewCostsResult};
+
+/// Extract per-rating review costs and rating probabilities from a revlog,
+/// ready to be spread into a `SimulatorConfig`.
+/// Ratings without data fall back to the fsrs-rs simulator defaults.
+#[napi]
+pub fn compute_review_costs(
+  #[napi(ts_arg_type = "Buffer | FSRSBindingRevlogEntry[] | FSRSBindingRevlogColumns")]
+  data: RevlogInput,
+  options: Option<CsvParseOptions>,
+) -> Result<ReviewCostsResult> {
+  let (mut revlogs, _) = read_revlog_input(&data, options.as_ref())?;
+  sort_revlogs(&mut revlogs);
+  Ok(extract_review_costs(&revlogs).into())
+}
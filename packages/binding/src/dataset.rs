@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use napi::bindgen_prelude::{Buffer, Either, Function, Result};
+use napi_derive::napi;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::{SliceRandom, index};
+
+use crate::FSRSItem;
+
+/// Leading bytes of an encoded dataset
+const MAGIC: &[u8; 4] = b"FSRS";
+/// Version of the encoding written by `encode`
+const FORMAT_VERSION: u16 = 1;
+
+/// A set of FSRS items owned by native code. Unlike an `FSRSBindingItem[]`, it can be
+/// passed to `computeParameters`, `evaluateWithTimeSeriesSplits`, `evaluate` and
+/// `universalMetrics` without keeping a JS object per item alive.
+///
+/// Calls share the items instead of converting them from JS objects. This does not
+/// lower peak memory: fsrs-rs takes its input by value, so every running
+/// computation works on its own copy of the items, next to the dataset's.
+#[napi(js_name = "FSRSBindingDataset")]
+#[derive(Debug, Clone)]
+pub struct FSRSDataset {
+  pub(crate) items: Arc<Vec<fsrs::FSRSItem>>,
+}
+
+impl From<Vec<fsrs::FSRSItem>> for FSRSDataset {
+  fn from(items: Vec<fsrs::FSRSItem>) -> Self {
+    Self {
+      items: Arc::new(items),
+    }
+  }
+}
+
+#[napi]
+impl FSRSDataset {
+  #[napi(constructor)]
+  pub fn new(items: Vec<&FSRSItem>) -> Self {
+    items
+      .into_iter()
+      .map(|item| item.inner.clone())
+      .collect::<Vec<_>>()
+      .into()
+  }
+
+  /// Number of items
+  #[napi(getter)]
+  pub fn length(&self) -> u32 {
+    self.items.len() as u32
+  }
+
+  #[napi]
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  /// Copy the items back into JS objects
+  #[napi]
+  pub fn items(&self) -> Vec<FSRSItem> {
+    self
+      .items
+      .iter()
+      .map(|item| FSRSItem {
+        inner: item.clone(),
+      })
+      .collect()
+  }
+
+  /// Serialize the dataset into a compact, versioned binary format
+  #[napi]
+  pub fn encode(&self) -> Buffer {
+    let mut out = Vec::with_capacity(10 + self.items.len() * 8);
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    out.extend_from_slice(&(self.items.len() as u32).to_le_bytes());
+    for item in self.items.iter() {
+      write_varint(&mut out, item.reviews.len() as u32);
+      for review in &item.reviews {
+        write_varint(&mut out, review.rating);
+        write_varint(&mut out, review.delta_t);
+      }
+    }
+    out.into()
+  }
+
+  /// Restore a dataset written by `encode`
+  #[napi(factory)]
+  pub fn decode(data: &[u8]) -> Result<Self> {
+    let mut reader = Reader { data, pos: 0 };
+    if reader.take(MAGIC.len())? != MAGIC {
+      return Err(napi::Error::from_reason(
+        "Not an encoded FSRS dataset".to_string(),
+      ));
+    }
+    let version = u16::from_le_bytes(reader.take(2)?.try_into().unwrap());
+    if version != FORMAT_VERSION {
+      return Err(napi::Error::from_reason(format!(
+        "Unsupported dataset format version {version}"
+      )));
+    }
+    let len = u32::from_le_bytes(reader.take(4)?.try_into().unwrap()) as usize;
+
+    // every item takes at least one byte, so don't trust a larger count
+    let mut items = Vec::with_capacity(len.min(data.len()));
+    for _ in 0..len {
+      let review_count = reader.varint()? as usize;
+      let reviews = (0..review_count)
+        .map(|_| {
+          let rating = reader.varint()?;
+          // same check as `FSRSBindingItem.fromJSON`
+          if !(1..=4).contains(&rating) {
+            return Err(napi::Error::from_reason(format!(
+              "Invalid rating {rating} in dataset: rating must be between 1 and 4"
+            )));
+          }
+          Ok(fsrs::FSRSReview {
+            rating,
+            delta_t: reader.varint()?,
+          })
+        })
+        .collect::<Result<_>>()?;
+      items.push(fsrs::FSRSItem { reviews });
+    }
+    if reader.pos != data.len() {
+      return Err(napi::Error::from_reason(
+        "Trailing data after encoded dataset".to_string(),
+      ));
+    }
+    Ok(items.into())
+  }
+
+  /// A new dataset with the items for which `predicate` returns true
+  #[napi]
+  pub fn filter(&self, predicate: Function<FSRSItem, bool>) -> Result<FSRSDataset> {
+    let mut items = Vec::new();
+    for item in self.items.iter() {
+      if predicate.call(FSRSItem {
+        inner: item.clone(),
+      })? {
+        items.push(item.clone());
+      }
+    }
+    Ok(items.into())
+  }
+
+  /// Split into `[first, rest]`, where `first` holds `ratio` of the items.
+  /// Items keep their order unless a `seed` is given, in which case they are shuffled first.
+  #[napi(ts_return_type = "[FSRSBindingDataset, FSRSBindingDataset]")]
+  pub fn split(&self, ratio: f64, seed: Option<u32>) -> Result<(FSRSDataset, FSRSDataset)> {
+    if !(0.0..=1.0).contains(&ratio) {
+      return Err(napi::Error::from_reason(
+        "ratio must be between 0 and 1".to_string(),
+      ));
+    }
+    let mut items = self.items.to_vec();
+    if let Some(seed) = seed {
+      items.shuffle(&mut StdRng::seed_from_u64(seed as u64));
+    }
+    let rest = items.split_off((items.len() as f64 * ratio).round() as usize);
+    Ok((items.into(), rest.into()))
+  }
+
+  /// A random subset of `count` items, in their original order
+  #[napi]
+  pub fn sample(&self, count: u32, seed: Option<u32>) -> Result<FSRSDataset> {
+    let count = count as usize;
+    if count > self.items.len() {
+      return Err(napi::Error::from_reason(format!(
+        "cannot sample {count} items from a dataset of {}",
+        self.items.len()
+      )));
+    }
+    let mut rng = match seed {
+      Some(seed) => StdRng::seed_from_u64(seed as u64),
+      None => StdRng::from_os_rng(),
+    };
+    let mut indices = index::sample(&mut rng, self.items.len(), count).into_vec();
+    indices.sort_unstable();
+    Ok(
+      indices
+        .into_iter()
+        .map(|i| self.items[i].clone())
+        .collect::<Vec<_>>()
+        .into(),
+    )
+  }
+}
+
+/// LEB128-encode `value`
+fn write_varint(out: &mut Vec<u8>, mut value: u32) {
+  while value >= 0x80 {
+    out.push(value as u8 | 0x80);
+    value >>= 7;
+  }
+  out.push(value as u8);
+}
+
+struct Reader<'a> {
+  data: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> Reader<'a> {
+  fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+    let bytes = self
+      .data
+      .get(self.pos..self.pos + n)
+      .ok_or_else(|| napi::Error::from_reason("Truncated dataset".to_string()))?;
+    self.pos += n;
+    Ok(bytes)
+  }
+
+  fn varint(&mut self) -> Result<u32> {
+    let mut value = 0u32;
+    for shift in (0..32).step_by(7) {
+      let byte = self.take(1)?[0];
+      if shift == 28 && byte > 0x0f {
+        break;
+      }
+      value |= ((byte & 0x7f) as u32) << shift;
+      if byte & 0x80 == 0 {
+        return Ok(value);
+      }
+    }
+    Err(napi::Error::from_reason(
+      "Invalid varint in dataset".to_string(),
+    ))
+  }
+}
+
+/// The items of a training set given as JS items or as a dataset.
+/// A dataset's items are shared here; `Arc::unwrap_or_clone` copies them only
+/// when they are handed to fsrs-rs, while JS items are converted once and moved.
+pub(crate) fn train_set_items(
+  train_set: Either<Vec<&FSRSItem>, &FSRSDataset>,
+) -> Arc<Vec<fsrs::FSRSItem>> {
+  match train_set {
+    Either::A(items) => Arc::new(items.into_iter().map(|item| item.inner.clone()).collect()),
+    Either::B(dataset) => Arc::clone(&dataset.items),
+  }
+}
@@ -1,18 +1,22 @@
-use napi::bindgen_prelude::{AsyncTask, Env, Result, Task};
+use fsrs_binding_core::prepare_items;
+use napi::bindgen_prelude::{AsyncTask, Either, Env, Result, Task};
 use napi_derive::napi;
 use std::sync::{Arc, Mutex};
 
+use crate::dataset::{FSRSDataset, train_set_items};
 use crate::{
-  ComputeParametersOptions, FSRSItem, ModelEvaluation, prepare_items,
-  progress::{self, ProgressState},
+  ComputeParametersOptions, FSRSItem, ModelEvaluation, ProgressEvent,
+  progress::{self, Deadline, ProgressEmitter, ProgressStateTrait, SplitProgressState},
 };
 
 pub struct EvaluateParametersTask {
-  pub(crate) train: Vec<fsrs::FSRSItem>,
-  pub(crate) state: Arc<Mutex<progress::ProgressState>>,
+  pub(crate) train: Arc<Vec<fsrs::FSRSItem>>,
+  pub(crate) state: Arc<Mutex<SplitProgressState>>,
   pub(crate) enable_short_term: bool,
   pub(crate) num_relearning_steps: Option<usize>,
   pub(crate) training_config: Option<fsrs::TrainingConfig>,
+  pub(crate) deadline: Option<Deadline>,
+  pub(crate) emitter: Option<Arc<Mutex<ProgressEmitter>>>,
   #[cfg(not(target_arch = "wasm32"))]
   pub(crate) timeout_ms: u32,
   #[cfg(not(target_arch = "wasm32"))]
@@ -26,6 +30,15 @@ impl Task for EvaluateParametersTask {
   type JsValue = ModelEvaluation;
 
   fn compute(&mut self) -> Result<Self::Output> {
+    if let Some(deadline) = self.deadline.filter(Deadline::passed) {
+      // WASM: the progress thread stops on its own once the deadline has passed
+      #[cfg(target_arch = "wasm32")]
+      if let Some(handle) = self.progress_thread.take() {
+        let _ = handle.join().ok();
+      }
+      return Err(deadline.exceeded());
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     let _progress_thread = {
       use crate::progress::spawn_progress_poller;
@@ -33,27 +46,43 @@ impl Task for EvaluateParametersTask {
         Arc::clone(&self.state),
         self.timeout_ms,
         self.progress_cb.take(),
+        self.deadline,
+        None,
       )
     };
 
     let state = Arc::clone(&self.state);
+    // fsrs-rs checks `want_abort` in here while it trains each split
+    let training = self.state.lock().map(|guard| Arc::clone(&guard.training));
     let input = fsrs::ComputeParametersInput {
       card_ids: None,
-      train_set: std::mem::take(&mut self.train),
-      progress: None,
+      // fsrs-rs takes the items by value; they are only copied if a dataset still holds them
+      train_set: prepare_items(Arc::unwrap_or_clone(std::mem::take(&mut self.train))),
+      progress: training.ok(),
       enable_short_term: self.enable_short_term,
       num_relearning_steps: self.num_relearning_steps,
       training_config: self.training_config,
     };
+    let emitter = self.emitter.clone();
     let result = fsrs::evaluate_with_time_series_splits(input, move |item_progress| {
+      // fsrs-rs reports progress once per time-series split
+      if let Some(emitter) = &emitter
+        && let Ok(mut emitter) = emitter.lock()
+      {
+        let (current, total) = (item_progress.current, item_progress.total);
+        emitter.emit(ProgressEvent {
+          split: Some(current as u32),
+          split_total: Some(total as u32),
+          ..ProgressEvent::new("evaluate", current, total)
+        });
+      }
       if let Ok(mut guard) = state.lock() {
         guard.current = item_progress.current;
         guard.total = item_progress.total;
-        return !guard.want_abort;
+        return !guard.want_abort();
       }
       true
-    })
-    .map_err(|e| napi::Error::from_reason(format!("evaluate_with_time_series_splits failed: {e}")));
+    });
 
     if let Ok(mut guard) = self.state.lock() {
       guard.finished = true;
@@ -68,7 +97,17 @@ impl Task for EvaluateParametersTask {
       let _ = handle.join().ok();
     }
 
-    result
+    if let (Ok(evaluation), Some(emitter)) = (&result, &self.emitter)
+      && let Ok(mut emitter) = emitter.lock()
+    {
+      let total = self.state.lock().map_or(0, |state| state.total);
+      emitter.finish("evaluate", total, Some(evaluation.log_loss as f64));
+    }
+
+    result.map_err(|e| match (e, self.deadline) {
+      (fsrs::FSRSError::Interrupted, Some(deadline)) if deadline.passed() => deadline.exceeded(),
+      (e, _) => napi::Error::from_reason(format!("evaluate_with_time_series_splits failed: {e}")),
+    })
   }
 
   fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
@@ -82,10 +121,18 @@ impl Task for EvaluateParametersTask {
 /// Evaluate parameters using time-series splits.
 #[napi(ts_return_type = "Promise<ModelEvaluation>", catch_unwind)]
 pub fn evaluate_with_time_series_splits(
-  train_set: Vec<&FSRSItem>,
-  #[napi(ts_arg_type = "ComputeParametersOptions")] options: Option<ComputeParametersOptions>,
+  #[napi(ts_arg_type = "FSRSBindingItem[] | FSRSBindingDataset")] train_set: Either<
+    Vec<&FSRSItem>,
+    &FSRSDataset,
+  >,
+  #[napi(ts_arg_type = "ComputeParametersOptions")] mut options: Option<ComputeParametersOptions>,
 ) -> AsyncTask<EvaluateParametersTask> {
-  let items = prepare_items(train_set);
+  let signal = options.as_mut().and_then(|x| x.signal.take());
+  let deadline = options
+    .as_ref()
+    .and_then(|x| x.deadline_ms)
+    .map(Deadline::after);
+  let items = train_set_items(train_set);
 
   let enable_short_term = options
     .as_ref()
@@ -102,7 +149,13 @@ pub fn evaluate_with_time_series_splits(
     .map(|x| x.to_fsrs_config());
   let timeout = options.as_ref().and_then(|x| x.timeout).unwrap_or(500);
 
-  let state = Arc::new(Mutex::new(ProgressState::default()));
+  let state = Arc::new(Mutex::new(SplitProgressState::default()));
+
+  let emitter = options
+    .as_ref()
+    .and_then(|x| x.on_progress_event.as_ref())
+    .and_then(|cb| cb.build_threadsafe_function().weak::<true>().build().ok())
+    .map(|callback| Arc::new(Mutex::new(ProgressEmitter::new(callback))));
 
   let progress_tsfn = options
     .as_ref()
@@ -112,14 +165,14 @@ pub fn evaluate_with_time_series_splits(
   // wasm: start polling here and do not pass callback into task
   #[cfg(target_arch = "wasm32")]
   let progress_thread_handle =
-    { progress::spawn_progress_poller(Arc::clone(&state), timeout, progress_tsfn) };
+    { progress::spawn_progress_poller(Arc::clone(&state), timeout, progress_tsfn, deadline, None) };
   // non-wasm reuses TSFN in task; wasm sets it to None (unused).
   #[cfg(not(target_arch = "wasm32"))]
   let progress_tsfn_for_task = progress_tsfn;
 
-  AsyncTask::new(EvaluateParametersTask {
+  let task = EvaluateParametersTask {
     train: items,
-    state,
+    state: Arc::clone(&state),
     #[cfg(not(target_arch = "wasm32"))]
     timeout_ms: timeout,
     #[cfg(not(target_arch = "wasm32"))]
@@ -127,7 +180,10 @@ pub fn evaluate_with_time_series_splits(
     enable_short_term,
     num_relearning_steps,
     training_config,
+    deadline,
+    emitter,
     #[cfg(target_arch = "wasm32")]
     progress_thread: Some(progress_thread_handle),
-  })
+  };
+  progress::with_abort_signal(task, &state, signal)
 }
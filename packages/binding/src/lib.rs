@@ -1,16 +1,32 @@
 #![deny(clippy::all)]
 
-use napi::bindgen_prelude::Result;
+use fsrs_binding_core::prepare_items;
+use napi::bindgen_prelude::{AsyncTask, Either, Result};
 use napi_derive::napi;
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+mod anki;
 mod convert;
+mod costs;
+mod dataset;
 mod evaluate;
+mod memory;
 mod model;
 mod progress;
+mod revlog;
+mod simulate;
 mod steps;
 mod train;
+#[cfg(not(target_arch = "wasm32"))]
+pub use anki::*;
 pub use convert::*;
+pub use costs::*;
+pub use dataset::*;
 pub use evaluate::*;
+pub use memory::*;
 pub use model::*;
+pub use revlog::*;
+pub use simulate::*;
 pub use steps::*;
 pub use train::*;
 
@@ -18,6 +34,7 @@ pub use train::*;
 #[derive(Debug)]
 pub struct FSRS {
   inner: fsrs::FSRS,
+  parameters: Vec<f32>,
 }
 
 #[napi]
@@ -25,15 +42,28 @@ impl FSRS {
   // allow users to create FSRS with custom parameters
   #[napi(constructor)]
   pub fn new(#[napi(ts_arg_type = "number[]")] parameters: Option<Vec<f64>>) -> Result<Self> {
-    let fsrs = match parameters {
+    let (fsrs, parameters) = match parameters {
       Some(p) if !p.is_empty() => {
         let params: Vec<f32> = p.iter().map(|&x| x as f32).collect();
-        fsrs::FSRS::new(&params)
-          .map_err(|e| napi::Error::from_reason(format!("Failed to create FSRS: {}", e)))?
+        let fsrs = fsrs::FSRS::new(&params)
+          .map_err(|e| napi::Error::from_reason(format!("Failed to create FSRS: {}", e)))?;
+        (fsrs, params)
       }
-      _ => fsrs::FSRS::default(),
+      _ => (fsrs::FSRS::default(), fsrs::DEFAULT_PARAMETERS.to_vec()),
     };
-    Ok(Self { inner: fsrs })
+    Ok(Self {
+      inner: fsrs,
+      parameters,
+    })
+  }
+
+  /// Forgetting curve decay of the model (w[20], or 0.5 for pre-FSRS-6 parameters)
+  #[napi(getter)]
+  pub fn decay(&self) -> f32 {
+    match self.parameters.len() {
+      21 => self.parameters[20],
+      _ => 0.5,
+    }
   }
 
   #[napi]
@@ -54,9 +84,80 @@ impl FSRS {
       .map_err(|e| napi::Error::from_reason(format!("Failed to get next states: {}", e)))
   }
 
+  /// Replay the full review history of `item` and return the resulting memory state.
+  /// `starting_state` seeds the first review, e.g. a state from `memoryStateFromSM2`.
+  #[napi]
+  pub fn memory_state(
+    &self,
+    item: &FSRSItem,
+    starting_state: Option<&MemoryState>,
+  ) -> Result<MemoryState> {
+    self
+      .inner
+      .memory_state(item.inner.clone(), starting_state.map(|x| x.inner))
+      .map(|inner| MemoryState { inner })
+      .map_err(|e| napi::Error::from_reason(format!("Failed to compute memory state: {}", e)))
+  }
+
+  /// Replay the review history of `item` and return the memory state after every review,
+  /// together with the retrievability predicted right before that review.
   #[napi]
-  pub fn evaluate(&self, train_set: Vec<&FSRSItem>) -> Result<ModelEvaluation> {
-    let items = prepare_items(train_set);
+  pub fn memory_state_history(
+    &self,
+    item: &FSRSItem,
+    starting_state: Option<&MemoryState>,
+  ) -> Result<Vec<MemoryStateStep>> {
+    let starting_state = starting_state.map(|x| x.inner);
+    let states = self
+      .inner
+      .historical_memory_states(item.inner.clone(), starting_state)
+      .map_err(|e| {
+        napi::Error::from_reason(format!("Failed to compute memory state history: {}", e))
+      })?;
+
+    let mut previous = starting_state;
+    Ok(
+      item
+        .inner
+        .reviews
+        .iter()
+        .zip(states)
+        .map(|(review, state)| {
+          let retrievability = previous
+            .map(|s| fsrs::current_retrievability(s, review.delta_t as f32, self.decay()) as f64);
+          previous = Some(state);
+          MemoryStateStep {
+            rating: review.rating,
+            delta_t: review.delta_t,
+            stability: state.stability as f64,
+            difficulty: state.difficulty as f64,
+            retrievability,
+          }
+        })
+        .collect(),
+    )
+  }
+
+  /// Compute the memory state of every item off the main thread,
+  /// returning one state per item in the same order.
+  /// Rejects if any item has no reviews.
+  #[napi(ts_return_type = "Promise<BindingMemoryState[]>", catch_unwind)]
+  pub fn memory_states(&self, items: Vec<&FSRSItem>) -> AsyncTask<MemoryStatesTask> {
+    AsyncTask::new(MemoryStatesTask {
+      parameters: self.parameters.clone(),
+      items: items.into_iter().map(|item| item.inner.clone()).collect(),
+    })
+  }
+
+  #[napi]
+  pub fn evaluate(
+    &self,
+    #[napi(ts_arg_type = "FSRSBindingItem[] | FSRSBindingDataset")] train_set: Either<
+      Vec<&FSRSItem>,
+      &FSRSDataset,
+    >,
+  ) -> Result<ModelEvaluation> {
+    let items = prepare_items(Arc::unwrap_or_clone(train_set_items(train_set)));
 
     // Because the computation finishes very quickly, progress reporting is not supported here
     let result = self.inner.evaluate(items, |_| true);
@@ -92,10 +193,13 @@ impl FSRS {
   #[napi]
   pub fn universal_metrics(
     &self,
-    train_set: Vec<&FSRSItem>,
+    #[napi(ts_arg_type = "FSRSBindingItem[] | FSRSBindingDataset")] train_set: Either<
+      Vec<&FSRSItem>,
+      &FSRSDataset,
+    >,
     parameter: Option<Vec<f64>>,
   ) -> Result<(f32, f32)> {
-    let items = prepare_items(train_set);
+    let items = prepare_items(Arc::unwrap_or_clone(train_set_items(train_set)));
 
     let params: Vec<f32> = match parameter {
       Some(p) if !p.is_empty() => p.iter().map(|&x| x as f32).collect(),
@@ -0,0 +1,53 @@
+use std::panic::AssertUnwindSafe;
+
+use napi::bindgen_prelude::{Env, Result, Task};
+
+use crate::MemoryState;
+
+/// Items are passed to fsrs-rs in chunks to bound the size of each padded batch
+const MEMORY_STATE_CHUNK_SIZE: usize = 4096;
+
+pub struct MemoryStatesTask {
+  pub(crate) parameters: Vec<f32>,
+  pub(crate) items: Vec<fsrs::FSRSItem>,
+}
+
+impl Task for MemoryStatesTask {
+  type Output = Vec<fsrs::MemoryState>;
+  type JsValue = Vec<MemoryState>;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let model = fsrs::FSRS::new(&self.parameters)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to create FSRS: {e}")))?;
+    let items = std::mem::take(&mut self.items);
+    // fsrs-rs cannot compute a memory state without any review
+    if let Some(index) = items.iter().position(|item| item.reviews.is_empty()) {
+      return Err(napi::Error::new(
+        napi::Status::InvalidArg,
+        format!("item {index} has no reviews"),
+      ));
+    }
+
+    // runs on a worker thread, where a panic would not be turned into a rejection
+    std::panic::catch_unwind(AssertUnwindSafe(|| {
+      let mut states = Vec::with_capacity(items.len());
+      for chunk in items.chunks(MEMORY_STATE_CHUNK_SIZE) {
+        let batch = model
+          .memory_state_batch(chunk.to_vec(), vec![None; chunk.len()])
+          .map_err(|e| napi::Error::from_reason(format!("Failed to compute memory states: {e}")))?;
+        states.extend(batch);
+      }
+      Ok(states)
+    }))
+    .unwrap_or_else(|_| Err(napi::Error::from_reason("Computing memory states panicked")))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(
+      output
+        .into_iter()
+        .map(|inner| MemoryState { inner })
+        .collect(),
+    )
+  }
+}
@@ -1,4 +1,6 @@
-use napi::bindgen_prelude::{FnArgs, Function};
+use napi::bindgen_prelude::{
+  AbortSignal, BigInt64Array, Either, FnArgs, Function, Uint8Array, Uint32Array,
+};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 
@@ -29,6 +31,15 @@ impl FSRSReview {
     self.inner.delta_t
   }
 
+  /// Rebuild a `FSRSBindingReview` from the output of `toString`
+  #[napi(factory, js_name = "fromJSON")]
+  pub fn from_json(json: String) -> napi::Result<Self> {
+    let review: ReviewJson = from_json(&json, "FSRSBindingReview")?;
+    Ok(Self {
+      inner: review.validate("FSRSBindingReview")?,
+    })
+  }
+
   #[napi(js_name = "toString")]
   pub fn to_string(&self) -> napi::Result<String> {
     serde_json::to_string(&self.inner)
@@ -88,6 +99,21 @@ impl FSRSItem {
     self.inner.reviews.iter().any(|review| review.delta_t > 0)
   }
 
+  /// Rebuild a `FSRSBindingItem` from the output of `toString`
+  #[napi(factory, js_name = "fromJSON")]
+  pub fn from_json(json: String) -> napi::Result<Self> {
+    let item: ItemJson = from_json(&json, "FSRSBindingItem")?;
+    Ok(Self {
+      inner: fsrs::FSRSItem {
+        reviews: item
+          .reviews
+          .into_iter()
+          .map(|review| review.validate("FSRSBindingItem"))
+          .collect::<napi::Result<_>>()?,
+      },
+    })
+  }
+
   #[napi(js_name = "toString")]
   pub fn to_string(&self) -> napi::Result<String> {
     serde_json::to_string(&self.inner)
@@ -100,6 +126,28 @@ impl FSRSItem {
   }
 }
 
+/// An FSRS item together with the revlog row its last review comes from
+#[napi(object, object_from_js = false, js_name = "FSRSBindingItemEntry")]
+pub struct FSRSItemEntry {
+  pub card_id: String,
+  /// Time of the item's last review, in milliseconds since the epoch
+  pub review_time: i64,
+  /// Index of the last review's row in the source data, excluding the header
+  pub row_index: u32,
+  pub item: FSRSItem,
+}
+
+impl From<fsrs_binding_core::ItemEntry> for FSRSItemEntry {
+  fn from(entry: fsrs_binding_core::ItemEntry) -> Self {
+    Self {
+      card_id: entry.card_id,
+      review_time: entry.review_time,
+      row_index: entry.row_index as u32,
+      item: FSRSItem { inner: entry.item },
+    }
+  }
+}
+
 #[napi(js_name = "BindingMemoryState")]
 #[derive(Debug)]
 pub struct MemoryState {
@@ -124,6 +172,43 @@ impl MemoryState {
   pub fn difficulty(&self) -> f64 {
     self.inner.difficulty as f64
   }
+
+  /// Probability of recall after `days_elapsed` days (fractional days allowed).
+  /// `decay` defaults to the FSRS-6 default; pass `FSRSBinding.decay` to match a model.
+  #[napi]
+  pub fn retrievability(&self, days_elapsed: f64, decay: Option<f64>) -> napi::Result<f64> {
+    let decay = resolve_decay(decay)?;
+    if !days_elapsed.is_finite() || days_elapsed < 0.0 {
+      return Err(napi::Error::from_reason(
+        "days_elapsed must be a finite, non-negative number".to_string(),
+      ));
+    }
+    Ok(fsrs::current_retrievability(self.inner, days_elapsed as f32, decay) as f64)
+  }
+
+  /// Number of days (fractional) until retrievability drops to `desired_retention`.
+  /// This is the inverse of `retrievability`.
+  #[napi]
+  pub fn interval(&self, desired_retention: f64, decay: Option<f64>) -> napi::Result<f64> {
+    let decay = resolve_decay(decay)?;
+    if desired_retention <= 0.0 || desired_retention >= 1.0 {
+      return Err(napi::Error::from_reason(
+        "desired_retention must be between 0 and 1 (exclusive)".to_string(),
+      ));
+    }
+    // the same f32 forgetting curve as `retrievability`, so the two round-trip
+    Ok(fsrs::next_interval(self.inner.stability, desired_retention as f32, decay) as f64)
+  }
+
+  /// Rebuild a `BindingMemoryState` from the output of `toString`
+  #[napi(factory, js_name = "fromJSON")]
+  pub fn from_json(json: String) -> napi::Result<Self> {
+    let state: MemoryStateJson = from_json(&json, "BindingMemoryState")?;
+    Ok(Self {
+      inner: state.validate("BindingMemoryState")?,
+    })
+  }
+
   #[napi(js_name = "toString")]
   pub fn to_string(&self) -> napi::Result<String> {
     serde_json::to_string(&serde_json::json!({
@@ -139,6 +224,16 @@ impl MemoryState {
   }
 }
 
+fn resolve_decay(decay: Option<f64>) -> napi::Result<f32> {
+  match decay {
+    None => Ok(fsrs::FSRS6_DEFAULT_DECAY),
+    Some(d) if d.is_finite() && d > 0.0 => Ok(d as f32),
+    Some(_) => Err(napi::Error::from_reason(
+      "decay must be a finite, positive number".to_string(),
+    )),
+  }
+}
+
 #[napi(js_name = "BindingNextStates")]
 #[derive(Debug)]
 pub struct NextStates {
@@ -171,6 +266,21 @@ impl NextStates {
     }
   }
 
+  /// Rebuild a `BindingNextStates` from the output of `toString`
+  #[napi(factory, js_name = "fromJSON")]
+  pub fn from_json(json: String) -> napi::Result<Self> {
+    let states: NextStatesJson = from_json(&json, "BindingNextStates")?;
+    let name = "BindingNextStates";
+    Ok(Self {
+      inner: fsrs::NextStates {
+        again: states.again.validate(name)?,
+        hard: states.hard.validate(name)?,
+        good: states.good.validate(name)?,
+        easy: states.easy.validate(name)?,
+      },
+    })
+  }
+
   #[napi(js_name = "toString")]
   pub fn to_string(&self) -> napi::Result<String> {
     serde_json::to_string(&serde_json::json!(self.inner))
@@ -201,6 +311,15 @@ impl ItemState {
     self.inner.interval
   }
 
+  /// Rebuild a `BindingItemState` from the output of `toString`
+  #[napi(factory, js_name = "fromJSON")]
+  pub fn from_json(json: String) -> napi::Result<Self> {
+    let state: ItemStateJson = from_json(&json, "BindingItemState")?;
+    Ok(Self {
+      inner: state.validate("BindingItemState")?,
+    })
+  }
+
   #[napi(js_name = "toString")]
   pub fn to_string(&self) -> napi::Result<String> {
     serde_json::to_string(&serde_json::json!(self.inner))
@@ -213,6 +332,116 @@ impl ItemState {
   }
 }
 
+// JSON layouts written by the `toString` methods above, read back by `fromJSON`
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str, type_name: &str) -> napi::Result<T> {
+  serde_json::from_str(json)
+    .map_err(|e| napi::Error::from_reason(format!("Invalid {type_name} JSON: {e}")))
+}
+
+fn invalid_json(type_name: &str, reason: &str) -> napi::Error {
+  napi::Error::from_reason(format!("Invalid {type_name} JSON: {reason}"))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReviewJson {
+  rating: u32,
+  delta_t: u32,
+}
+
+impl ReviewJson {
+  fn validate(self, type_name: &str) -> napi::Result<fsrs::FSRSReview> {
+    if !(1..=4).contains(&self.rating) {
+      return Err(invalid_json(type_name, "rating must be between 1 and 4"));
+    }
+    Ok(fsrs::FSRSReview {
+      rating: self.rating,
+      delta_t: self.delta_t,
+    })
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ItemJson {
+  reviews: Vec<ReviewJson>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MemoryStateJson {
+  stability: f32,
+  difficulty: f32,
+}
+
+impl MemoryStateJson {
+  fn validate(self, type_name: &str) -> napi::Result<fsrs::MemoryState> {
+    if !self.stability.is_finite() || self.stability <= 0.0 {
+      return Err(invalid_json(
+        type_name,
+        "stability must be a positive number",
+      ));
+    }
+    if !self.difficulty.is_finite() {
+      return Err(invalid_json(
+        type_name,
+        "difficulty must be a finite number",
+      ));
+    }
+    Ok(fsrs::MemoryState {
+      stability: self.stability,
+      difficulty: self.difficulty,
+    })
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ItemStateJson {
+  memory: MemoryStateJson,
+  interval: f32,
+}
+
+impl ItemStateJson {
+  fn validate(self, type_name: &str) -> napi::Result<fsrs::ItemState> {
+    if !self.interval.is_finite() || self.interval < 0.0 {
+      return Err(invalid_json(
+        type_name,
+        "interval must be a non-negative number",
+      ));
+    }
+    Ok(fsrs::ItemState {
+      memory: self.memory.validate(type_name)?,
+      interval: self.interval,
+    })
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NextStatesJson {
+  again: ItemStateJson,
+  hard: ItemStateJson,
+  good: ItemStateJson,
+  easy: ItemStateJson,
+}
+
+#[napi(object)]
+pub struct MemoryStateStep {
+  /// Rating of the review, 1-4
+  pub rating: u32,
+  /// Days elapsed since the previous review
+  pub delta_t: u32,
+  /// Stability after the review
+  pub stability: f64,
+  /// Difficulty after the review
+  pub difficulty: f64,
+  /// Retrievability predicted just before the review.
+  /// Absent for the first review when no starting state is given.
+  pub retrievability: Option<f64>,
+}
+
 #[napi(object)]
 pub struct ModelEvaluation {
   pub log_loss: f64,
@@ -238,6 +467,23 @@ pub struct StepRatingStats {
   pub stability: f64,
 }
 
+impl From<fsrs_binding_core::StepRatingStats> for StepRatingStats {
+  fn from(stats: fsrs_binding_core::StepRatingStats) -> Self {
+    Self {
+      count: stats.count,
+      delay_q1: stats.delay_q1,
+      delay_q2: stats.delay_q2,
+      delay_q3: stats.delay_q3,
+      r1: stats.r1,
+      r2: stats.r2,
+      r3: stats.r3,
+      r4: stats.r4,
+      retention: stats.retention,
+      stability: stats.stability,
+    }
+  }
+}
+
 #[napi(object)]
 pub struct StepStatsResult {
   pub again: Option<StepRatingStats>,
@@ -250,9 +496,337 @@ pub struct StepStatsResult {
   pub recommended_learning_steps: Vec<i64>,
   /// Recommended relearning steps in seconds
   pub recommended_relearning_steps: Vec<i64>,
+  /// Rows skipped in lenient mode
+  pub skipped_rows: Vec<CsvRowError>,
+}
+
+impl From<fsrs_binding_core::StepStats> for StepStatsResult {
+  fn from(stats: fsrs_binding_core::StepStats) -> Self {
+    Self {
+      again: stats.again.map(Into::into),
+      hard: stats.hard.map(Into::into),
+      good: stats.good.map(Into::into),
+      again_then_good: stats.again_then_good.map(Into::into),
+      good_then_again: stats.good_then_again.map(Into::into),
+      relearning: stats.relearning.map(Into::into),
+      recommended_learning_steps: stats.recommended_learning_steps,
+      recommended_relearning_steps: stats.recommended_relearning_steps,
+      skipped_rows: Vec::new(),
+    }
+  }
+}
+
+/// A single review, as an alternative to a revlog CSV row
+#[napi(object, js_name = "FSRSBindingRevlogEntry")]
+pub struct RevlogInputEntry {
+  #[napi(ts_type = "string | number")]
+  pub card_id: Either<String, i64>,
+  /// Milliseconds since the Unix epoch
+  pub review_time: i64,
+  pub review_rating: u32,
+  pub review_state: u32,
+  pub review_duration: u32,
+}
+
+/// Reviews as parallel columns; every column must have the same length
+#[napi(object, js_name = "FSRSBindingRevlogColumns")]
+pub struct RevlogColumns {
+  pub card_ids: BigInt64Array,
+  /// Milliseconds since the Unix epoch
+  pub review_times: BigInt64Array,
+  pub review_ratings: Uint8Array,
+  pub review_states: Uint8Array,
+  pub review_durations: Uint32Array,
+}
+
+#[napi(object)]
+pub struct RevlogStats {
+  pub review_count: u32,
+  pub card_count: u32,
+  /// Milliseconds since the Unix epoch
+  pub first_review_time: Option<i64>,
+  /// Milliseconds since the Unix epoch
+  pub last_review_time: Option<i64>,
+  /// Number of Again, Hard, Good and Easy ratings
+  pub rating_counts: Vec<u32>,
+  /// Sum of review durations in milliseconds
+  pub total_duration: f64,
+}
+
+#[napi(object)]
+pub struct CsvParseOptions {
+  /// Skip malformed rows instead of failing. Defaults to false.
+  pub lenient: Option<bool>,
+  pub schema: Option<CsvSchema>,
+}
+
+/// Options of `convertCsvToFsrsItems` and its variants
+#[napi(object, object_to_js = false)]
+pub struct ConvertOptions<'env> {
+  /// Skip malformed rows instead of failing. Defaults to false.
+  pub lenient: Option<bool>,
+  pub schema: Option<CsvSchema>,
+  /// Returns the UTC offset in minutes for a review time, overriding `timezone`
+  #[napi(ts_type = "(ms: number, timezone: string) => number")]
+  pub offset_provider: Option<OffsetFunc<'env>>,
+}
+
+impl ConvertOptions<'_> {
+  pub(crate) fn parse_options(&self) -> CsvParseOptions {
+    CsvParseOptions {
+      lenient: self.lenient,
+      schema: self.schema.clone(),
+    }
+  }
+}
+
+/// Layout of a revlog CSV that differs from the default
+/// `card_id,review_time,review_rating,review_state,review_duration` export
+#[napi(object)]
+#[derive(Clone)]
+pub struct CsvSchema {
+  pub columns: Option<CsvColumns>,
+  /// Field delimiter, e.g. "\t" or ";". Defaults to ",".
+  pub delimiter: Option<String>,
+  /// Unit of `review_time`: epoch seconds, epoch milliseconds or an ISO-8601
+  /// timestamp with offset. Defaults to "ms".
+  #[napi(ts_type = "'s' | 'ms' | 'iso8601'")]
+  pub timestamp_unit: Option<String>,
+}
+
+/// Header names of the revlog columns; unset columns keep their default name
+#[napi(object)]
+#[derive(Clone)]
+pub struct CsvColumns {
+  pub card_id: Option<String>,
+  pub review_time: Option<String>,
+  pub review_rating: Option<String>,
+  pub review_state: Option<String>,
+  pub review_duration: Option<String>,
+}
+
+/// A CSV row that could not be parsed
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct CsvRowError {
+  /// 1-based line number in the source data; the header is line 1
+  pub line: u32,
+  /// Name of the offending column, if known
+  pub column: Option<String>,
+  /// Raw value of the offending field, if known
+  pub value: Option<String>,
+  pub message: String,
+}
+
+impl From<fsrs_binding_core::CsvRowError> for CsvRowError {
+  fn from(error: fsrs_binding_core::CsvRowError) -> Self {
+    Self {
+      line: error.line,
+      column: error.column,
+      value: error.value,
+      message: error.message,
+    }
+  }
+}
+
+#[napi(object, object_from_js = false)]
+pub struct ConvertCsvReport {
+  pub items: Vec<FSRSItem>,
+  /// Rows skipped in lenient mode
+  pub skipped_rows: Vec<CsvRowError>,
+}
+
+#[napi(object)]
+pub struct SimulatorConfig {
+  /// Total number of cards in the deck
+  pub deck_size: u32,
+  /// Number of days to simulate
+  pub learn_span: u32,
+  /// Maximum number of new cards introduced per day
+  pub learn_limit: u32,
+  /// Maximum number of reviews per day
+  pub review_limit: u32,
+  /// Desired retention used for scheduling. Defaults to 0.9.
+  pub desired_retention: Option<f64>,
+  /// Maximum interval in days
+  pub max_interval: Option<u32>,
+  /// Maximum time in seconds spent per day
+  pub max_cost_per_day: Option<f64>,
+  /// Whether new cards are still introduced once the review limit is reached
+  pub new_cards_ignore_review_limit: Option<bool>,
+  /// Probability of each first rating [again, hard, good, easy]
+  pub first_rating_prob: Option<Vec<f64>>,
+  /// Probability of each successful review rating [hard, good, easy]
+  pub review_rating_prob: Option<Vec<f64>>,
+  /// Seconds spent on a learning card per rating [again, hard, good, easy]
+  pub learn_costs: Option<Vec<f64>>,
+  /// Seconds spent on a review card per rating [again, hard, good, easy]
+  pub review_costs: Option<Vec<f64>>,
+  /// Seconds spent on a relearning card per rating [again, hard, good, easy]
+  pub relearn_costs: Option<Vec<f64>>,
+  /// Random seed of the simulation
+  pub seed: Option<u32>,
+}
+
+fn to_f32_array<const N: usize>(values: &[f64], name: &str) -> napi::Result<[f32; N]> {
+  let values: Vec<f32> = values.iter().map(|&x| x as f32).collect();
+  values
+    .try_into()
+    .map_err(|_| napi::Error::from_reason(format!("{name} must have exactly {N} elements")))
+}
+
+impl SimulatorConfig {
+  pub(crate) fn desired_retention(&self) -> f32 {
+    self.desired_retention.unwrap_or(0.9) as f32
+  }
+
+  pub(crate) fn to_fsrs_config(&self) -> napi::Result<fsrs::SimulatorConfig> {
+    let mut config = fsrs::SimulatorConfig {
+      deck_size: self.deck_size as usize,
+      learn_span: self.learn_span as usize,
+      learn_limit: self.learn_limit as usize,
+      review_limit: self.review_limit as usize,
+      ..Default::default()
+    };
+    if let Some(max_interval) = self.max_interval {
+      config.max_ivl = max_interval as f32;
+    }
+    if let Some(max_cost_per_day) = self.max_cost_per_day {
+      config.max_cost_perday = max_cost_per_day as f32;
+    }
+    if let Some(ignore) = self.new_cards_ignore_review_limit {
+      config.new_cards_ignore_review_limit = ignore;
+    }
+    if let Some(prob) = &self.first_rating_prob {
+      config.first_rating_prob = to_f32_array(prob, "firstRatingProb")?;
+    }
+    if let Some(prob) = &self.review_rating_prob {
+      config.review_rating_prob = to_f32_array(prob, "reviewRatingProb")?;
+    }
+    // state_rating_costs rows: learning, review, relearning
+    if let Some(costs) = &self.learn_costs {
+      config.state_rating_costs[0] = to_f32_array(costs, "learnCosts")?;
+    }
+    if let Some(costs) = &self.review_costs {
+      config.state_rating_costs[1] = to_f32_array(costs, "reviewCosts")?;
+    }
+    if let Some(costs) = &self.relearn_costs {
+      config.state_rating_costs[2] = to_f32_array(costs, "relearnCosts")?;
+    }
+    Ok(config)
+  }
+}
+
+/// Review costs and rating probabilities extracted from a revlog.
+/// Field names match `SimulatorConfig`, so the result can be spread into it.
+#[napi(object)]
+pub struct ReviewCostsResult {
+  /// Probability of each first rating [again, hard, good, easy]
+  pub first_rating_prob: Vec<f64>,
+  /// Probability of each successful review rating [hard, good, easy]
+  pub review_rating_prob: Vec<f64>,
+  /// Median seconds spent on a learning card per rating [again, hard, good, easy]
+  pub learn_costs: Vec<f64>,
+  /// Median seconds spent on a review card per rating [again, hard, good, easy]
+  pub review_costs: Vec<f64>,
+  /// Median seconds spent on a relearning card per rating [again, hard, good, easy]
+  pub relearn_costs: Vec<f64>,
+}
+
+impl From<fsrs_binding_core::ReviewCosts> for ReviewCostsResult {
+  fn from(costs: fsrs_binding_core::ReviewCosts) -> Self {
+    Self {
+      first_rating_prob: costs.first_rating_prob,
+      review_rating_prob: costs.review_rating_prob,
+      learn_costs: costs.learn_costs,
+      review_costs: costs.review_costs,
+      relearn_costs: costs.relearn_costs,
+    }
+  }
+}
+
+#[napi(object)]
+pub struct SimulationResult {
+  /// Expected number of memorized cards at the end of each day
+  pub memorized_cnt_per_day: Vec<f64>,
+  /// Number of reviews on each day
+  pub review_cnt_per_day: Vec<u32>,
+  /// Number of new cards learned on each day
+  pub learn_cnt_per_day: Vec<u32>,
+  /// Time in seconds spent on each day
+  pub cost_per_day: Vec<f64>,
+}
+
+impl From<fsrs::SimulationResult> for SimulationResult {
+  fn from(result: fsrs::SimulationResult) -> Self {
+    Self {
+      memorized_cnt_per_day: result
+        .memorized_cnt_per_day
+        .iter()
+        .map(|&x| x as f64)
+        .collect(),
+      review_cnt_per_day: result
+        .review_cnt_per_day
+        .iter()
+        .map(|&x| x as u32)
+        .collect(),
+      learn_cnt_per_day: result.learn_cnt_per_day.iter().map(|&x| x as u32).collect(),
+      cost_per_day: result.cost_per_day.iter().map(|&x| x as f64).collect(),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct WorkloadPoint {
+  pub desired_retention: f64,
+  /// Total time in seconds spent over the simulated span
+  pub cost: f64,
+  /// Total number of reviews over the simulated span
+  pub review_count: u32,
+  /// Expected number of memorized cards at the end of the span
+  pub memorized: f64,
 }
 
 type ProgressFunc<'env> = Function<'env, FnArgs<(u32, u32)>, Option<bool>>;
+type ProgressEventFunc<'env> = Function<'env, ProgressEvent, ()>;
+pub(crate) type OffsetFunc<'env> = Function<'env, FnArgs<(i64, String)>, i32>;
+
+/// A change in the progress of an optimization or evaluation
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressEvent {
+  #[napi(ts_type = "'pretrain' | 'train' | 'evaluate'")]
+  pub phase: String,
+  /// Time-series splits completed so far, during the train and evaluate phases
+  pub split: Option<u32>,
+  pub split_total: Option<u32>,
+  /// Current training epoch of the split in progress, during the train phase
+  pub epoch: Option<u32>,
+  pub epoch_total: Option<u32>,
+  pub current: u32,
+  pub total: u32,
+  /// Estimated milliseconds until the phase completes, once progress was made
+  pub eta_ms: Option<f64>,
+  /// Log loss of the evaluation, on its last event. Training events carry no
+  /// loss: fsrs-rs does not report it.
+  pub loss: Option<f64>,
+}
+
+impl ProgressEvent {
+  pub(crate) fn new(phase: &str, current: usize, total: usize) -> Self {
+    Self {
+      phase: phase.to_string(),
+      split: None,
+      split_total: None,
+      epoch: None,
+      epoch_total: None,
+      current: current as u32,
+      total: total as u32,
+      eta_ms: None,
+      loss: None,
+    }
+  }
+}
 
 #[napi(object)]
 pub struct TrainingConfig {
@@ -283,7 +857,7 @@ impl TrainingConfig {
   }
 }
 
-#[napi(object)]
+#[napi(object, object_to_js = false)]
 pub struct ComputeParametersOptions<'env> {
   /// Whether to enable short-term memory parameters
   pub enable_short_term: bool,
@@ -294,6 +868,67 @@ pub struct ComputeParametersOptions<'env> {
   // Progress callback temporarily disabled for v3 migration
   #[napi(ts_type = "(current: number, total: number) => boolean | undefined | void")]
   pub progress: Option<ProgressFunc<'env>>,
+  /// Interval in milliseconds at which progress is polled. Defaults to 500.
+  #[napi(ts_type = "number")]
+  pub timeout: Option<u32>,
+  /// Cancels the computation as soon as it is aborted; the promise then rejects
+  #[napi(ts_type = "AbortSignal")]
+  pub signal: Option<AbortSignal>,
+  /// Receives a `ProgressEvent` whenever the phase, split, epoch or progress changes.
+  /// Training progress is polled every `timeout` milliseconds, so short epochs can
+  /// be skipped; evaluation reports every split. Once the computation succeeds,
+  /// the last event reports it as complete.
+  #[napi(ts_type = "(event: ProgressEvent) => void")]
+  pub on_progress_event: Option<ProgressEventFunc<'env>>,
+  /// Wall-clock limit in milliseconds, counted from the call. When it passes, the
+  /// computation stops and the promise rejects with a `Cancelled` error whose
+  /// message starts with `DeadlineExceeded`. fsrs-rs stops at its next training
+  /// progress check after the deadline, also inside an evaluation split, so the
+  /// promise can settle slightly later.
+  ///
+  /// There is no best-so-far result: fsrs-rs does not expose the parameters
+  /// of an unfinished training run.
+  pub deadline_ms: Option<u32>,
+}
+
+#[napi(object)]
+pub struct ComputeOptimalRetentionOptions<'env> {
+  /// Lowest desired retention considered. Defaults to 0.7.
+  pub min_retention: Option<f64>,
+  /// Highest desired retention considered. Defaults to 0.95.
+  pub max_retention: Option<f64>,
+  /// Simulations, each with its own seed, averaged per candidate retention.
+  /// More samples give a less noisy result at a proportional cost. Defaults to 4.
+  pub samples: Option<u32>,
+  #[napi(ts_type = "(current: number, total: number) => boolean | undefined | void")]
+  pub progress: Option<ProgressFunc<'env>>,
+  #[napi(ts_type = "number")]
+  pub timeout: Option<u32>,
+}
+
+#[napi(object)]
+pub struct WorkloadCurveOptions<'env> {
+  /// Lowest desired retention to simulate. Defaults to 0.7.
+  pub min_retention: Option<f64>,
+  /// Highest desired retention to simulate. Defaults to 0.99.
+  pub max_retention: Option<f64>,
+  /// Distance between two simulated retentions. Defaults to 0.01.
+  /// Steps giving more than 1000 points are rejected.
+  pub step: Option<f64>,
+  #[napi(ts_type = "(current: number, total: number) => boolean | undefined | void")]
+  pub progress: Option<ProgressFunc<'env>>,
+  #[napi(ts_type = "number")]
+  pub timeout: Option<u32>,
+}
+
+#[napi(object)]
+pub struct ConvertCsvOptions<'env> {
+  /// Skip malformed rows instead of failing. Defaults to false.
+  pub lenient: Option<bool>,
+  pub schema: Option<CsvSchema>,
+  /// Reports rows parsed plus cards converted. Return `false` to cancel.
+  #[napi(ts_type = "(current: number, total: number) => boolean | undefined | void")]
+  pub progress: Option<ProgressFunc<'env>>,
   #[napi(ts_type = "number")]
   pub timeout: Option<u32>,
 }
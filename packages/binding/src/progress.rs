@@ -1,14 +1,21 @@
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use napi::Status;
-use napi::bindgen_prelude::FnArgs;
+use napi::bindgen_prelude::{AbortSignal, AsyncTask, FnArgs, Task};
 use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
 
+use crate::ProgressEvent;
+
 type ProgressArgs = FnArgs<(u32, u32)>;
 pub type ProgressCallback =
   ThreadsafeFunction<ProgressArgs, Option<bool>, ProgressArgs, Status, false, true>;
+pub type ProgressEventCallback =
+  ThreadsafeFunction<ProgressEvent, (), ProgressEvent, Status, false, true>;
+
+/// Called by the progress poller with the state on every poll
+pub type PollHook<S> = Box<dyn FnMut(&S) + Send>;
 
 // ============================================================================
 // Unified progress state trait
@@ -42,8 +49,7 @@ impl ProgressStateTrait for fsrs::CombinedProgressState {
   }
 }
 
-// TODO: evaluate_with_time_series_splits support progress callback
-// Local progress state for evaluate and other operations
+// Local progress state for conversion, simulation and other operations
 #[derive(Debug, Default)]
 pub struct ProgressState {
   pub current: usize,
@@ -70,30 +76,221 @@ impl ProgressStateTrait for ProgressState {
   }
 }
 
+// Split progress for evaluate. The abort flag lives in the training state
+// fsrs-rs shares with every split, so an abort also stops the split in flight.
+#[derive(Debug)]
+pub struct SplitProgressState {
+  pub current: usize,
+  pub total: usize,
+  pub finished: bool,
+  pub training: Arc<Mutex<fsrs::CombinedProgressState>>,
+}
+
+impl Default for SplitProgressState {
+  fn default() -> Self {
+    Self {
+      current: 0,
+      total: 0,
+      finished: false,
+      training: fsrs::CombinedProgressState::new_shared(),
+    }
+  }
+}
+
+impl ProgressStateTrait for SplitProgressState {
+  fn current(&self) -> usize {
+    self.current
+  }
+  fn total(&self) -> usize {
+    self.total
+  }
+  fn finished(&self) -> bool {
+    self.finished
+  }
+  fn want_abort(&self) -> bool {
+    self.training.lock().is_ok_and(|guard| guard.want_abort)
+  }
+  fn set_want_abort(&mut self, value: bool) {
+    if let Ok(mut guard) = self.training.lock() {
+      guard.want_abort = value;
+    }
+  }
+}
+
+/// Sends a `ProgressEvent` to JS whenever the progress changes, with an ETA
+/// extrapolated from the time spent in the current phase
+pub struct ProgressEmitter {
+  callback: ProgressEventCallback,
+  phase_start: Instant,
+  last: Option<ProgressEvent>,
+}
+
+impl ProgressEmitter {
+  pub fn new(callback: ProgressEventCallback) -> Self {
+    Self {
+      callback,
+      phase_start: Instant::now(),
+      last: None,
+    }
+  }
+
+  pub fn emit(&mut self, mut event: ProgressEvent) {
+    match &self.last {
+      Some(last) if *last == event => return,
+      Some(last) if last.phase != event.phase => self.phase_start = Instant::now(),
+      _ => {}
+    }
+    self.last = Some(event.clone());
+
+    let (current, total) = (event.current, event.total);
+    if current > 0 && total >= current {
+      let elapsed = self.phase_start.elapsed().as_secs_f64() * 1000.0;
+      event.eta_ms = Some(elapsed * (total - current) as f64 / current as f64);
+    }
+    let _ = self
+      .callback
+      .call(event, ThreadsafeFunctionCallMode::NonBlocking);
+  }
+
+  /// Report the computation as complete, repeating the last event or, if there
+  /// was none, starting `phase` at its end
+  pub fn finish(&mut self, phase: &str, total: usize, loss: Option<f64>) {
+    let last = self
+      .last
+      .clone()
+      .unwrap_or_else(|| ProgressEvent::new(phase, total, total));
+    self.emit(ProgressEvent {
+      current: last.total,
+      loss,
+      ..last
+    });
+  }
+}
+
+/// The training split in progress, or pretraining until fsrs-rs has set up the splits
+fn training_event(state: &fsrs::CombinedProgressState) -> ProgressEvent {
+  let in_progress = state
+    .splits
+    .iter()
+    .position(|s| s.epoch < s.epoch_total || s.items_processed < s.items_total)
+    .or(state.splits.len().checked_sub(1));
+  match in_progress {
+    Some(index) if state.total() > 0 => {
+      let split = &state.splits[index];
+      ProgressEvent {
+        split: Some(index as u32),
+        split_total: Some(state.splits.len() as u32),
+        epoch: Some(split.epoch as u32),
+        epoch_total: Some(split.epoch_total as u32),
+        ..ProgressEvent::new("train", state.current(), state.total())
+      }
+    }
+    _ => ProgressEvent::new("pretrain", 0, 0),
+  }
+}
+
+/// Emits training events from the progress poller. `compute_parameters` has no
+/// progress callback; fsrs-rs only updates the shared `CombinedProgressState`.
+pub fn training_events(
+  emitter: &Arc<Mutex<ProgressEmitter>>,
+) -> PollHook<fsrs::CombinedProgressState> {
+  let emitter = Arc::clone(emitter);
+  Box::new(move |state| {
+    if let Ok(mut emitter) = emitter.lock() {
+      emitter.emit(training_event(state));
+    }
+  })
+}
+
+/// A wall-clock limit, counted from when the computation was requested
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+  start: Instant,
+  ms: u32,
+}
+
+impl Deadline {
+  pub fn after(ms: u32) -> Self {
+    Self {
+      start: Instant::now(),
+      ms,
+    }
+  }
+
+  fn remaining(&self) -> Duration {
+    (self.start + Duration::from_millis(self.ms as u64)).saturating_duration_since(Instant::now())
+  }
+
+  pub fn passed(&self) -> bool {
+    self.remaining().is_zero()
+  }
+
+  /// The error a computation stopped by the deadline rejects with
+  pub fn exceeded(&self) -> napi::Error {
+    napi::Error::new(
+      Status::Cancelled,
+      format!("DeadlineExceeded: no result within {} ms", self.ms),
+    )
+  }
+}
+
+/// Wrap `task` so that aborting `signal` rejects the promise and sets `want_abort`
+/// right away, instead of waiting for the next progress callback.
+pub fn with_abort_signal<T: Task, S: ProgressStateTrait>(
+  task: T,
+  state: &Arc<Mutex<S>>,
+  signal: Option<AbortSignal>,
+) -> AsyncTask<T> {
+  let Some(signal) = signal else {
+    return AsyncTask::new(task);
+  };
+  let state = Arc::clone(state);
+  signal.on_abort(move || {
+    if let Ok(mut guard) = state.lock() {
+      guard.set_want_abort(true);
+    }
+  });
+  AsyncTask::with_signal(task, signal)
+}
+
 /// Spawns a lightweight progress polling thread that:
 /// - Periodically reads progress from `fsrs::CombinedProgressState`
 /// - Logs progress changes
 /// - Sends (current, total) to JS if callback is provided
 /// - Handles abort requests from JS callback failures
+/// - Sets `want_abort` once `deadline` passes, waking up for it if needed
+/// - Runs `on_poll` on every poll, including the one that sees the computation finish
 pub fn spawn_progress_poller<S: ProgressStateTrait>(
   state: Arc<Mutex<S>>,
   timeout_ms: u32,
   tsfn: Option<ProgressCallback>,
+  deadline: Option<Deadline>,
+  mut on_poll: Option<PollHook<S>>,
 ) -> thread::JoinHandle<()> {
   thread::spawn(move || {
     let mut last = (0usize, 1usize);
     let sleep_dur = Duration::from_millis(timeout_ms as u64);
 
     loop {
+      if deadline.is_some_and(|deadline| deadline.passed()) {
+        if let Ok(mut guard) = state.lock() {
+          guard.set_want_abort(true);
+        }
+        break;
+      }
+
       // Read progress under lock, then drop lock before reporting or sleeping
       let (cur, tot, finished, already_aborted) = {
         let Ok(g) = state.lock() else {
           break; // Lock poisoned, exit polling thread
         };
+        if let Some(on_poll) = &mut on_poll {
+          on_poll(&g);
+        }
         (g.current(), g.total().max(1), g.finished(), g.want_abort())
       };
 
-      if finished || already_aborted {
+      if already_aborted {
         break;
       }
 
@@ -143,7 +340,14 @@ pub fn spawn_progress_poller<S: ProgressStateTrait>(
         }
       }
 
-      std::thread::sleep(sleep_dur);
+      // stop only once the final progress has been reported
+      if finished {
+        break;
+      }
+
+      std::thread::sleep(
+        deadline.map_or(sleep_dur, |deadline| sleep_dur.min(deadline.remaining())),
+      );
     }
   })
 }
@@ -0,0 +1,137 @@
+use fsrs_binding_core::{RevlogEntry, extract_review_costs, sort_revlogs};
+use itertools::Itertools;
+use napi::bindgen_prelude::{Either, FnArgs, Function, Result};
+use napi_derive::napi;
+
+use crate::FSRSItem as FSRSBindingItem;
+use crate::convert::{
+  OffsetProvider, RevlogInput, convert_sorted_revlogs_to_fsrs_entries, read_revlog_input,
+};
+use crate::dataset::FSRSDataset;
+use crate::steps::compute_step_stats;
+use crate::{
+  CsvParseOptions, CsvRowError, FSRSItemEntry, ReviewCostsResult, RevlogStats, StepStatsResult,
+};
+
+/// A revlog parsed once and kept in native memory, so that several analyses
+/// can run on the same data without parsing and sorting it again.
+#[napi(js_name = "FSRSBindingRevlog")]
+pub struct Revlog {
+  /// Sorted by (card_id, review_time)
+  entries: Vec<RevlogEntry>,
+  skipped_rows: Vec<CsvRowError>,
+}
+
+#[napi]
+impl Revlog {
+  #[napi(constructor)]
+  pub fn new(
+    #[napi(ts_arg_type = "Buffer | FSRSBindingRevlogEntry[] | FSRSBindingRevlogColumns")]
+    data: RevlogInput,
+    options: Option<CsvParseOptions>,
+  ) -> Result<Self> {
+    let (mut entries, skipped_rows) = read_revlog_input(&data, options.as_ref())?;
+    sort_revlogs(&mut entries);
+    Ok(Self {
+      entries,
+      skipped_rows,
+    })
+  }
+
+  /// Number of reviews
+  #[napi(getter)]
+  pub fn length(&self) -> u32 {
+    self.entries.len() as u32
+  }
+
+  /// Rows skipped in lenient mode
+  #[napi(getter)]
+  pub fn skipped_rows(&self) -> Vec<CsvRowError> {
+    self.skipped_rows.clone()
+  }
+
+  #[napi]
+  pub fn stats(&self) -> RevlogStats {
+    let mut rating_counts = vec![0; 4];
+    for entry in &self.entries {
+      if (1..=4).contains(&entry.review_rating) {
+        rating_counts[entry.review_rating as usize - 1] += 1;
+      }
+    }
+    RevlogStats {
+      review_count: self.entries.len() as u32,
+      card_count: self.entries.iter().map(|r| &r.card_id).dedup().count() as u32,
+      first_review_time: self.entries.iter().map(|r| r.review_time).min(),
+      last_review_time: self.entries.iter().map(|r| r.review_time).max(),
+      rating_counts,
+      total_duration: self.entries.iter().map(|r| r.review_duration as f64).sum(),
+    }
+  }
+
+  /// Same as `convertCsvToFsrsItems`, without parsing the revlog again
+  #[napi]
+  pub fn to_fsrs_items(
+    &self,
+    next_day_starts_at: i64,
+    timezone: String,
+    #[napi(ts_arg_type = "(ms: number, timezone: string) => number")] offset_provider: Option<
+      Function<FnArgs<(i64, String)>, i32>,
+    >,
+  ) -> Result<Vec<FSRSBindingItem>> {
+    let entries = self.to_fsrs_item_entries(next_day_starts_at, timezone, offset_provider)?;
+    Ok(entries.into_iter().map(|entry| entry.item).collect())
+  }
+
+  /// Like `toFsrsItems`, keeping the items in native memory
+  #[napi]
+  pub fn to_fsrs_dataset(
+    &self,
+    next_day_starts_at: i64,
+    timezone: String,
+    #[napi(ts_arg_type = "(ms: number, timezone: string) => number")] offset_provider: Option<
+      Function<FnArgs<(i64, String)>, i32>,
+    >,
+  ) -> Result<FSRSDataset> {
+    let entries = self.to_fsrs_item_entries(next_day_starts_at, timezone, offset_provider)?;
+    Ok(
+      entries
+        .into_iter()
+        .map(|entry| entry.item.inner)
+        .collect::<Vec<_>>()
+        .into(),
+    )
+  }
+
+  /// Same as `convertCsvToFsrsItemEntries`, without parsing the revlog again
+  #[napi]
+  pub fn to_fsrs_item_entries(
+    &self,
+    next_day_starts_at: i64,
+    timezone: String,
+    #[napi(ts_arg_type = "(ms: number, timezone: string) => number")] offset_provider: Option<
+      Function<FnArgs<(i64, String)>, i32>,
+    >,
+  ) -> Result<Vec<FSRSItemEntry>> {
+    let offset_provider = OffsetProvider::new(&timezone, offset_provider.as_ref())?;
+    convert_sorted_revlogs_to_fsrs_entries(&self.entries, next_day_starts_at, &offset_provider)
+  }
+
+  /// Same as `computeOptimalSteps`, without parsing the revlog again
+  #[napi]
+  pub fn compute_optimal_steps(
+    &self,
+    desired_retention: f64,
+    #[napi(ts_arg_type = "number | number[]")] decay_or_params: Either<f64, Vec<f64>>,
+  ) -> Result<StepStatsResult> {
+    Ok(StepStatsResult {
+      skipped_rows: self.skipped_rows.clone(),
+      ..compute_step_stats(&self.entries, desired_retention, &decay_or_params)?
+    })
+  }
+
+  /// Same as `computeReviewCosts`, without parsing the revlog again
+  #[napi]
+  pub fn compute_review_costs(&self) -> ReviewCostsResult {
+    extract_review_costs(&self.entries).into()
+  }
+}
@@ -0,0 +1,219 @@
+use napi::bindgen_prelude::{AsyncTask, Env, Result, Task};
+use napi_derive::napi;
+use std::sync::{Arc, Mutex};
+
+use crate::progress::{self, ProgressState};
+use crate::{SimulationResult, SimulatorConfig, WorkloadCurveOptions, WorkloadPoint};
+
+pub struct SimulateTask {
+  pub(crate) parameters: Vec<f32>,
+  pub(crate) config: fsrs::SimulatorConfig,
+  pub(crate) desired_retention: f32,
+  pub(crate) seed: Option<u64>,
+}
+
+impl Task for SimulateTask {
+  type Output = fsrs::SimulationResult;
+  type JsValue = SimulationResult;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    fsrs::simulate(
+      &self.config,
+      &self.parameters,
+      self.desired_retention,
+      self.seed,
+      None,
+    )
+    .map_err(|e| napi::Error::from_reason(format!("simulate failed: {e}")))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output.into())
+  }
+}
+
+/// Simulate reviewing a deck day by day and report the daily workload.
+#[napi(ts_return_type = "Promise<SimulationResult>", catch_unwind)]
+pub fn simulate(
+  #[napi(ts_arg_type = "number[]")] parameters: Option<Vec<f64>>,
+  config: SimulatorConfig,
+) -> Result<AsyncTask<SimulateTask>> {
+  let parameters: Vec<f32> = match parameters {
+    Some(p) if !p.is_empty() => p.iter().map(|&x| x as f32).collect(),
+    _ => fsrs::DEFAULT_PARAMETERS.to_vec(),
+  };
+
+  Ok(AsyncTask::new(SimulateTask {
+    parameters,
+    desired_retention: config.desired_retention(),
+    seed: config.seed.map(|x| x as u64),
+    config: config.to_fsrs_config()?,
+  }))
+}
+
+/// Upper bound on the number of simulations a single workload curve may run
+const MAX_WORKLOAD_POINTS: usize = 1000;
+
+pub struct WorkloadCurveTask {
+  pub(crate) parameters: Vec<f32>,
+  pub(crate) config: fsrs::SimulatorConfig,
+  pub(crate) retentions: Vec<f64>,
+  pub(crate) seed: Option<u64>,
+  pub(crate) state: Arc<Mutex<ProgressState>>,
+  #[cfg(not(target_arch = "wasm32"))]
+  pub(crate) timeout_ms: u32,
+  #[cfg(not(target_arch = "wasm32"))]
+  pub(crate) progress_cb: Option<progress::ProgressCallback>,
+  #[cfg(target_arch = "wasm32")]
+  pub(crate) progress_thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl WorkloadCurveTask {
+  fn sweep(&self) -> Result<Vec<WorkloadPoint>> {
+    if let Ok(mut guard) = self.state.lock() {
+      guard.total = self.retentions.len();
+    }
+    let mut points = Vec::with_capacity(self.retentions.len());
+    for &desired_retention in &self.retentions {
+      let result = fsrs::simulate(
+        &self.config,
+        &self.parameters,
+        desired_retention as f32,
+        self.seed,
+        None,
+      )
+      .map_err(|e| napi::Error::from_reason(format!("simulate failed: {e}")))?;
+      points.push(WorkloadPoint {
+        desired_retention,
+        cost: result.cost_per_day.iter().map(|&x| x as f64).sum(),
+        review_count: result.review_cnt_per_day.iter().sum::<usize>() as u32,
+        memorized: result.memorized_cnt_per_day.last().copied().unwrap_or(0.0) as f64,
+      });
+
+      let Ok(mut guard) = self.state.lock() else {
+        continue;
+      };
+      guard.current += 1;
+      if guard.want_abort {
+        return Err(napi::Error::from_reason("compute_workload_curve aborted"));
+      }
+    }
+    Ok(points)
+  }
+}
+
+impl Task for WorkloadCurveTask {
+  type Output = Vec<WorkloadPoint>;
+  type JsValue = Vec<WorkloadPoint>;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let _progress_thread = {
+      use crate::progress::spawn_progress_poller;
+      spawn_progress_poller(
+        Arc::clone(&self.state),
+        self.timeout_ms,
+        self.progress_cb.take(),
+        None,
+        None,
+      )
+    };
+
+    let result = self.sweep();
+
+    if let Ok(mut guard) = self.state.lock() {
+      guard.finished = true;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = _progress_thread.join().ok();
+
+    // WASM: join the progress thread
+    #[cfg(target_arch = "wasm32")]
+    if let Some(handle) = self.progress_thread.take() {
+      let _ = handle.join().ok();
+    }
+
+    result
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+/// Simulate a range of desired retentions and report the workload and
+/// knowledge retained at each one.
+/// `simulatorConfig.desiredRetention` is ignored.
+#[napi(ts_return_type = "Promise<WorkloadPoint[]>", catch_unwind)]
+pub fn compute_workload_curve(
+  #[napi(ts_arg_type = "number[]")] parameters: Option<Vec<f64>>,
+  simulator_config: SimulatorConfig,
+  #[napi(ts_arg_type = "WorkloadCurveOptions")] options: Option<WorkloadCurveOptions>,
+) -> Result<AsyncTask<WorkloadCurveTask>> {
+  let parameters: Vec<f32> = match parameters {
+    Some(p) if !p.is_empty() => p.iter().map(|&x| x as f32).collect(),
+    _ => fsrs::DEFAULT_PARAMETERS.to_vec(),
+  };
+
+  let min = options
+    .as_ref()
+    .and_then(|x| x.min_retention)
+    .unwrap_or(0.7);
+  let max = options
+    .as_ref()
+    .and_then(|x| x.max_retention)
+    .unwrap_or(0.99);
+  let step = options.as_ref().and_then(|x| x.step).unwrap_or(0.01);
+  if !(0.0 < min && min <= max && max < 1.0) {
+    return Err(napi::Error::from_reason(
+      "retention range must satisfy 0 < minRetention <= maxRetention < 1".to_string(),
+    ));
+  }
+  if !step.is_finite() || step <= 0.0 {
+    return Err(napi::Error::from_reason(
+      "step must be a finite, positive number".to_string(),
+    ));
+  }
+  let steps = ((max - min) / step + 1e-9).floor();
+  if steps >= MAX_WORKLOAD_POINTS as f64 {
+    return Err(napi::Error::from_reason(format!(
+      "step is too small: the curve would have more than {MAX_WORKLOAD_POINTS} points"
+    )));
+  }
+  let count = steps as usize + 1;
+  let retentions = (0..count).map(|i| min + i as f64 * step).collect();
+
+  let seed = simulator_config.seed.map(|x| x as u64);
+  let config = simulator_config.to_fsrs_config()?;
+
+  let state = Arc::new(Mutex::new(ProgressState::default()));
+  let timeout = options.as_ref().and_then(|x| x.timeout).unwrap_or(500);
+
+  let progress_tsfn = options
+    .as_ref()
+    .and_then(|x| x.progress.as_ref())
+    .and_then(|cb| cb.build_threadsafe_function().weak::<true>().build().ok());
+
+  // wasm: start polling here and do not pass callback into task
+  #[cfg(target_arch = "wasm32")]
+  let progress_thread_handle =
+    { progress::spawn_progress_poller(Arc::clone(&state), timeout, progress_tsfn, None, None) };
+  // non-wasm reuses TSFN in task; wasm does not pass callback into task.
+  #[cfg(not(target_arch = "wasm32"))]
+  let progress_tsfn_for_task = progress_tsfn;
+
+  Ok(AsyncTask::new(WorkloadCurveTask {
+    parameters,
+    config,
+    retentions,
+    seed,
+    state,
+    #[cfg(not(target_arch = "wasm32"))]
+    timeout_ms: timeout,
+    #[cfg(not(target_arch = "wasm32"))]
+    progress_cb: progress_tsfn_for_task,
+    #[cfg(target_arch = "wasm32")]
+    progress_thread: Some(progress_thread_handle),
+  }))
+}
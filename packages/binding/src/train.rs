@@ -1,17 +1,19 @@
-use napi::bindgen_prelude::{AsyncTask, Env, Result, Task};
+use napi::bindgen_prelude::{AsyncTask, Either, Env, Result, Task};
 use napi_derive::napi;
 use std::sync::{Arc, Mutex};
 
-#[cfg(not(target_arch = "wasm32"))]
-use crate::progress;
-use crate::{ComputeParametersOptions, FSRSItem};
+use crate::dataset::{FSRSDataset, train_set_items};
+use crate::progress::{self, Deadline, ProgressEmitter, ProgressState};
+use crate::{ComputeOptimalRetentionOptions, ComputeParametersOptions, FSRSItem, SimulatorConfig};
 
 pub struct ComputeParametersTask {
-  pub(crate) train: Vec<fsrs::FSRSItem>,
+  pub(crate) train: Arc<Vec<fsrs::FSRSItem>>,
   pub(crate) state: Arc<Mutex<fsrs::CombinedProgressState>>,
   pub(crate) enable_short_term: bool,
   pub(crate) num_relearning_steps: Option<usize>,
   pub(crate) training_config: Option<fsrs::TrainingConfig>,
+  pub(crate) deadline: Option<Deadline>,
+  pub(crate) emitter: Option<Arc<Mutex<ProgressEmitter>>>,
   #[cfg(not(target_arch = "wasm32"))]
   pub(crate) timeout_ms: u32,
   #[cfg(not(target_arch = "wasm32"))]
@@ -25,6 +27,15 @@ impl Task for ComputeParametersTask {
   type JsValue = Vec<f64>;
 
   fn compute(&mut self) -> Result<Self::Output> {
+    if let Some(deadline) = self.deadline.filter(Deadline::passed) {
+      // WASM: the progress thread stops on its own once the deadline has passed
+      #[cfg(target_arch = "wasm32")]
+      if let Some(handle) = self.progress_thread.take() {
+        let _ = handle.join().ok();
+      }
+      return Err(deadline.exceeded());
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     let _progress_thread = {
       use crate::progress::spawn_progress_poller;
@@ -32,18 +43,20 @@ impl Task for ComputeParametersTask {
         Arc::clone(&self.state),
         self.timeout_ms,
         self.progress_cb.take(),
+        self.deadline,
+        self.emitter.as_ref().map(progress::training_events),
       )
     };
 
     let out = fsrs::compute_parameters(fsrs::ComputeParametersInput {
       card_ids: None,
-      train_set: std::mem::take(&mut self.train),
+      // fsrs-rs takes the items by value; they are only copied if a dataset still holds them
+      train_set: Arc::unwrap_or_clone(std::mem::take(&mut self.train)),
       progress: Some(Arc::clone(&self.state)),
       enable_short_term: self.enable_short_term,
       num_relearning_steps: self.num_relearning_steps,
       training_config: self.training_config,
-    })
-    .map_err(|e| napi::Error::from_reason(format!("compute_parameters failed: {e}")))?;
+    });
 
     #[cfg(not(target_arch = "wasm32"))]
     let _ = _progress_thread.join().ok();
@@ -54,7 +67,18 @@ impl Task for ComputeParametersTask {
       let _ = handle.join().ok();
     }
 
-    Ok(out)
+    // fsrs-rs does not report the training loss, so the last event carries none
+    if let (Ok(_), Some(emitter)) = (&out, &self.emitter)
+      && let Ok(mut emitter) = emitter.lock()
+    {
+      let total = self.state.lock().map_or(0, |state| state.total());
+      emitter.finish("train", total, None);
+    }
+
+    out.map_err(|e| match (e, self.deadline) {
+      (fsrs::FSRSError::Interrupted, Some(deadline)) if deadline.passed() => deadline.exceeded(),
+      (e, _) => napi::Error::from_reason(format!("compute_parameters failed: {e}")),
+    })
   }
 
   fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
@@ -65,13 +89,18 @@ impl Task for ComputeParametersTask {
 /// Calculate appropriate parameters for the provided review history.
 #[napi(ts_return_type = "Promise<number[]>", catch_unwind)]
 pub fn compute_parameters(
-  train_set: Vec<&FSRSItem>,
-  #[napi(ts_arg_type = "ComputeParametersOptions")] options: Option<ComputeParametersOptions>,
+  #[napi(ts_arg_type = "FSRSBindingItem[] | FSRSBindingDataset")] train_set: Either<
+    Vec<&FSRSItem>,
+    &FSRSDataset,
+  >,
+  #[napi(ts_arg_type = "ComputeParametersOptions")] mut options: Option<ComputeParametersOptions>,
 ) -> AsyncTask<ComputeParametersTask> {
-  let train_data: Vec<fsrs::FSRSItem> = train_set
-    .into_iter()
-    .map(|item| item.inner.clone())
-    .collect();
+  let signal = options.as_mut().and_then(|x| x.signal.take());
+  let deadline = options
+    .as_ref()
+    .and_then(|x| x.deadline_ms)
+    .map(Deadline::after);
+  let train_data = train_set_items(train_set);
 
   let state = fsrs::CombinedProgressState::new_shared();
   let timeout = options.as_ref().and_then(|x| x.timeout).unwrap_or(500);
@@ -81,6 +110,12 @@ pub fn compute_parameters(
     .and_then(|x| x.progress.as_ref())
     .and_then(|cb| cb.build_threadsafe_function().weak::<true>().build().ok());
 
+  let emitter = options
+    .as_ref()
+    .and_then(|x| x.on_progress_event.as_ref())
+    .and_then(|cb| cb.build_threadsafe_function().weak::<true>().build().ok())
+    .map(|callback| Arc::new(Mutex::new(ProgressEmitter::new(callback))));
+
   // wasm: start polling here and do not pass callback into task
   #[cfg(target_arch = "wasm32")]
   let progress_thread_handle = {
@@ -89,6 +124,8 @@ pub fn compute_parameters(
       Arc::clone(&state),
       timeout,
       progress_tsfn,
+      deadline,
+      emitter.as_ref().map(progress::training_events),
     ))
   };
   // non-wasm reuses TSFN in task; wasm does not pass callback into task.
@@ -110,9 +147,9 @@ pub fn compute_parameters(
     .and_then(|x| x.training_config.as_ref())
     .map(|x| x.to_fsrs_config());
 
-  AsyncTask::new(ComputeParametersTask {
+  let task = ComputeParametersTask {
     train: train_data,
-    state,
+    state: Arc::clone(&state),
     #[cfg(not(target_arch = "wasm32"))]
     timeout_ms: timeout,
     #[cfg(not(target_arch = "wasm32"))]
@@ -120,7 +157,226 @@ pub fn compute_parameters(
     enable_short_term,
     num_relearning_steps,
     training_config,
+    deadline,
+    emitter,
     #[cfg(target_arch = "wasm32")]
     progress_thread: progress_thread_handle,
-  })
+  };
+  progress::with_abort_signal(task, &state, signal)
+}
+
+const OPTIMAL_RETENTION_TOLERANCE: f64 = 0.01;
+/// Golden-section search ratio, (sqrt(5) - 1) / 2
+const INV_PHI: f64 = 0.6180339887498949;
+
+/// Range searched for the optimal retention and how many simulations are averaged per candidate
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetentionSearch {
+  pub(crate) min: f64,
+  pub(crate) max: f64,
+  pub(crate) samples: u64,
+}
+
+impl RetentionSearch {
+  fn from_options(options: Option<&ComputeOptimalRetentionOptions>) -> Result<Self> {
+    let min = options.and_then(|x| x.min_retention).unwrap_or(0.7);
+    let max = options.and_then(|x| x.max_retention).unwrap_or(0.95);
+    let samples = options.and_then(|x| x.samples).unwrap_or(4);
+    if !(0.0 < min && min < max && max < 1.0) {
+      return Err(napi::Error::from_reason(
+        "retention range must satisfy 0 < minRetention < maxRetention < 1".to_string(),
+      ));
+    }
+    if samples == 0 {
+      return Err(napi::Error::from_reason(
+        "samples must be at least 1".to_string(),
+      ));
+    }
+    Ok(Self {
+      min,
+      max,
+      samples: samples as u64,
+    })
+  }
+}
+
+/// Average time spent per memorized card at the end of the simulation.
+fn cost_per_memorized(
+  config: &fsrs::SimulatorConfig,
+  parameters: &[f32],
+  desired_retention: f64,
+  samples: u64,
+  mut progress: impl FnMut() -> bool,
+) -> Result<f64> {
+  let mut total = 0.0;
+  for seed in 0..samples {
+    let result = fsrs::simulate(
+      config,
+      parameters,
+      desired_retention as f32,
+      Some(seed),
+      None,
+    )
+    .map_err(|e| napi::Error::from_reason(format!("simulate failed: {e}")))?;
+    let cost: f64 = result.cost_per_day.iter().map(|&x| x as f64).sum();
+    let memorized = result.memorized_cnt_per_day.last().copied().unwrap_or(0.0) as f64;
+    total += if memorized > 0.0 {
+      cost / memorized
+    } else {
+      f64::INFINITY
+    };
+    if !progress() {
+      return Err(napi::Error::from_reason(
+        "compute_optimal_retention aborted",
+      ));
+    }
+  }
+  Ok(total / samples as f64)
+}
+
+/// Golden-section search for the desired retention with the lowest cost per memorized card.
+fn optimal_retention(
+  config: &fsrs::SimulatorConfig,
+  parameters: &[f32],
+  search: RetentionSearch,
+  state: &Mutex<ProgressState>,
+) -> Result<f32> {
+  let mut iterations = 0;
+  let mut width = search.max - search.min;
+  while width > OPTIMAL_RETENTION_TOLERANCE {
+    width *= INV_PHI;
+    iterations += 1;
+  }
+  if let Ok(mut guard) = state.lock() {
+    guard.total = (iterations + 2) * search.samples as usize;
+  }
+  let mut step = || {
+    let Ok(mut guard) = state.lock() else {
+      return true;
+    };
+    guard.current += 1;
+    !guard.want_abort
+  };
+
+  let (mut low, mut high) = (search.min, search.max);
+  let mut x1 = high - INV_PHI * (high - low);
+  let mut x2 = low + INV_PHI * (high - low);
+  let mut f1 = cost_per_memorized(config, parameters, x1, search.samples, &mut step)?;
+  let mut f2 = cost_per_memorized(config, parameters, x2, search.samples, &mut step)?;
+  for _ in 0..iterations {
+    if f1 < f2 {
+      high = x2;
+      x2 = x1;
+      f2 = f1;
+      x1 = high - INV_PHI * (high - low);
+      f1 = cost_per_memorized(config, parameters, x1, search.samples, &mut step)?;
+    } else {
+      low = x1;
+      x1 = x2;
+      f1 = f2;
+      x2 = low + INV_PHI * (high - low);
+      f2 = cost_per_memorized(config, parameters, x2, search.samples, &mut step)?;
+    }
+  }
+  Ok(((high + low) / 2.0) as f32)
+}
+
+pub struct ComputeOptimalRetentionTask {
+  pub(crate) parameters: Vec<f32>,
+  pub(crate) config: fsrs::SimulatorConfig,
+  pub(crate) search: RetentionSearch,
+  pub(crate) state: Arc<Mutex<ProgressState>>,
+  #[cfg(not(target_arch = "wasm32"))]
+  pub(crate) timeout_ms: u32,
+  #[cfg(not(target_arch = "wasm32"))]
+  pub(crate) progress_cb: Option<progress::ProgressCallback>,
+  #[cfg(target_arch = "wasm32")]
+  pub(crate) progress_thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Task for ComputeOptimalRetentionTask {
+  type Output = f32;
+  type JsValue = f64;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let _progress_thread = {
+      use crate::progress::spawn_progress_poller;
+      spawn_progress_poller(
+        Arc::clone(&self.state),
+        self.timeout_ms,
+        self.progress_cb.take(),
+        None,
+        None,
+      )
+    };
+
+    let result = optimal_retention(&self.config, &self.parameters, self.search, &self.state);
+
+    if let Ok(mut guard) = self.state.lock() {
+      guard.finished = true;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = _progress_thread.join().ok();
+
+    // WASM: join the progress thread
+    #[cfg(target_arch = "wasm32")]
+    if let Some(handle) = self.progress_thread.take() {
+      let _ = handle.join().ok();
+    }
+
+    result
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output as f64)
+  }
+}
+
+/// Find the desired retention that minimizes the simulated time spent per memorized card.
+/// `simulatorConfig.desiredRetention` is ignored.
+#[napi(ts_return_type = "Promise<number>", catch_unwind)]
+pub fn compute_optimal_retention(
+  #[napi(ts_arg_type = "number[]")] parameters: Option<Vec<f64>>,
+  simulator_config: SimulatorConfig,
+  #[napi(ts_arg_type = "ComputeOptimalRetentionOptions")] options: Option<
+    ComputeOptimalRetentionOptions,
+  >,
+) -> Result<AsyncTask<ComputeOptimalRetentionTask>> {
+  let parameters: Vec<f32> = match parameters {
+    Some(p) if !p.is_empty() => p.iter().map(|&x| x as f32).collect(),
+    _ => fsrs::DEFAULT_PARAMETERS.to_vec(),
+  };
+  let config = simulator_config.to_fsrs_config()?;
+  let search = RetentionSearch::from_options(options.as_ref())?;
+
+  let state = Arc::new(Mutex::new(ProgressState::default()));
+  let timeout = options.as_ref().and_then(|x| x.timeout).unwrap_or(500);
+
+  let progress_tsfn = options
+    .as_ref()
+    .and_then(|x| x.progress.as_ref())
+    .and_then(|cb| cb.build_threadsafe_function().weak::<true>().build().ok());
+
+  // wasm: start polling here and do not pass callback into task
+  #[cfg(target_arch = "wasm32")]
+  let progress_thread_handle =
+    { progress::spawn_progress_poller(Arc::clone(&state), timeout, progress_tsfn, None, None) };
+  // non-wasm reuses TSFN in task; wasm does not pass callback into task.
+  #[cfg(not(target_arch = "wasm32"))]
+  let progress_tsfn_for_task = progress_tsfn;
+
+  Ok(AsyncTask::new(ComputeOptimalRetentionTask {
+    parameters,
+    config,
+    search,
+    state,
+    #[cfg(not(target_arch = "wasm32"))]
+    timeout_ms: timeout,
+    #[cfg(not(target_arch = "wasm32"))]
+    progress_cb: progress_tsfn_for_task,
+    #[cfg(target_arch = "wasm32")]
+    progress_thread: Some(progress_thread_handle),
+  }))
 }
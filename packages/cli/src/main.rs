@@ -0,0 +1,206 @@
+//! `fsrs`: convert revlog CSV exports and optimize FSRS parameters without Node.
+//!
+//! Every subcommand reads a revlog CSV from disk and prints its result as JSON
+//! on stdout, using the same code as the Node binding.
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand};
+use fsrs_binding_core::{CsvSchema, Decay, RevlogEntry};
+
+#[derive(Parser)]
+#[command(name = "fsrs", version, about)]
+struct Cli {
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Convert a revlog into FSRS items
+  Convert {
+    #[command(flatten)]
+    revlog: RevlogArgs,
+    #[command(flatten)]
+    days: DayArgs,
+  },
+  /// Compute FSRS parameters for a revlog
+  Optimize {
+    #[command(flatten)]
+    revlog: RevlogArgs,
+    #[command(flatten)]
+    days: DayArgs,
+    #[command(flatten)]
+    training: TrainingArgs,
+  },
+  /// Evaluate the optimizer on a revlog using time-series splits
+  Evaluate {
+    #[command(flatten)]
+    revlog: RevlogArgs,
+    #[command(flatten)]
+    days: DayArgs,
+    #[command(flatten)]
+    training: TrainingArgs,
+  },
+  /// Analyse learning steps and recommend (re)learning steps
+  Steps {
+    #[command(flatten)]
+    revlog: RevlogArgs,
+    /// Retention the recommended steps should reach
+    #[arg(long, default_value_t = 0.9)]
+    desired_retention: f64,
+    /// Forgetting curve decay. Defaults to the decay of the default parameters.
+    #[arg(long, conflicts_with = "parameters")]
+    decay: Option<f64>,
+    /// FSRS-6 parameters, comma separated; w[20] is used as the decay
+    #[arg(long, value_delimiter = ',')]
+    parameters: Option<Vec<f64>>,
+  },
+}
+
+#[derive(Args)]
+struct RevlogArgs {
+  /// Revlog CSV, by default with the header
+  /// `card_id,review_time,review_rating,review_state,review_duration`
+  path: PathBuf,
+  /// Field delimiter
+  #[arg(long, default_value_t = ',')]
+  delimiter: char,
+  /// Unit of `review_time`: "s", "ms" or "iso8601"
+  #[arg(long, default_value = "ms")]
+  timestamp_unit: String,
+  /// Skip malformed rows instead of failing, reporting them on stderr
+  #[arg(long)]
+  lenient: bool,
+}
+
+#[derive(Args)]
+struct DayArgs {
+  /// Hour at which a new day starts
+  #[arg(long, default_value_t = 4)]
+  next_day_starts_at: i64,
+  /// IANA time zone used for day boundaries
+  #[arg(long, default_value = "UTC")]
+  timezone: String,
+}
+
+#[derive(Args)]
+struct TrainingArgs {
+  /// Do not fit the short-term memory parameters
+  #[arg(long)]
+  no_short_term: bool,
+  /// Number of relearning steps
+  #[arg(long)]
+  num_relearning_steps: Option<usize>,
+}
+
+impl TrainingArgs {
+  fn input(&self, train_set: Vec<fsrs::FSRSItem>) -> fsrs::ComputeParametersInput {
+    fsrs::ComputeParametersInput {
+      card_ids: None,
+      train_set,
+      progress: None,
+      enable_short_term: !self.no_short_term,
+      num_relearning_steps: self.num_relearning_steps,
+      training_config: None,
+    }
+  }
+}
+
+impl RevlogArgs {
+  fn read(&self) -> Result<Vec<RevlogEntry>, Box<dyn Error>> {
+    if !self.delimiter.is_ascii() {
+      return Err(
+        format!(
+          "delimiter must be a single ASCII character, got {:?}",
+          self.delimiter
+        )
+        .into(),
+      );
+    }
+    let schema = CsvSchema {
+      delimiter: self.delimiter as u8,
+      timestamp_unit: self.timestamp_unit.parse()?,
+      ..CsvSchema::default()
+    };
+
+    let data = std::fs::read(&self.path)
+      .map_err(|e| format!("Failed to read {}: {e}", self.path.display()))?;
+    let (revlogs, skipped_rows) = fsrs_binding_core::read_revlog_csv(&data, &schema, self.lenient)?;
+    for row in skipped_rows {
+      eprintln!("skipped {row}");
+    }
+    Ok(revlogs)
+  }
+}
+
+impl DayArgs {
+  fn convert(&self, revlogs: Vec<RevlogEntry>) -> Result<Vec<fsrs::FSRSItem>, Box<dyn Error>> {
+    let timezone = fsrs_binding_core::time_zone(&self.timezone)?;
+    Ok(fsrs_binding_core::convert_revlogs_to_fsrs_items(
+      revlogs,
+      self.next_day_starts_at,
+      &timezone,
+    )?)
+  }
+}
+
+fn run(command: Command) -> Result<serde_json::Value, Box<dyn Error>> {
+  let output = match command {
+    Command::Convert { revlog, days } => serde_json::to_value(days.convert(revlog.read()?)?)?,
+    Command::Optimize {
+      revlog,
+      days,
+      training,
+    } => {
+      let items = days.convert(revlog.read()?)?;
+      let parameters = fsrs::compute_parameters(training.input(items))
+        .map_err(|e| format!("compute_parameters failed: {e}"))?;
+      serde_json::to_value(parameters)?
+    }
+    Command::Evaluate {
+      revlog,
+      days,
+      training,
+    } => {
+      let items = fsrs_binding_core::prepare_items(days.convert(revlog.read()?)?);
+      let evaluation = fsrs::evaluate_with_time_series_splits(training.input(items), |_| true)
+        .map_err(|e| format!("evaluate_with_time_series_splits failed: {e}"))?;
+      serde_json::json!({
+        "log_loss": evaluation.log_loss,
+        "rmse_bins": evaluation.rmse_bins,
+      })
+    }
+    Command::Steps {
+      revlog,
+      desired_retention,
+      decay,
+      parameters,
+    } => {
+      let decay = match &parameters {
+        Some(parameters) => Decay::Parameters(parameters),
+        None => Decay::Value(decay.unwrap_or(fsrs::DEFAULT_PARAMETERS[20] as f64)),
+      };
+      let stats =
+        fsrs_binding_core::compute_optimal_steps(revlog.read()?, desired_retention, decay)?;
+      serde_json::to_value(stats)?
+    }
+  };
+  Ok(output)
+}
+
+fn main() -> ExitCode {
+  let cli = Cli::parse();
+  match run(cli.command) {
+    Ok(output) => {
+      println!("{output}");
+      ExitCode::SUCCESS
+    }
+    Err(e) => {
+      eprintln!("fsrs: {e}");
+      ExitCode::FAILURE
+    }
+  }
+}
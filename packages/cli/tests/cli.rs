@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use assert_cmd::Command;
+use assert_cmd::assert::Assert;
+use fsrs_binding_core::testing::synthetic_revlog;
+use predicates::str::contains;
+use serde_json::Value;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/revlog.csv");
+
+fn fsrs(args: &[&str]) -> Assert {
+  Command::cargo_bin("fsrs").unwrap().args(args).assert()
+}
+
+fn json(assert: Assert) -> Value {
+  serde_json::from_slice(&assert.success().get_output().stdout).unwrap()
+}
+
+/// Write `csv` to a file in the target's temporary directory
+fn write_revlog(name: &str, csv: &str) -> PathBuf {
+  let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+  std::fs::write(&path, csv).unwrap();
+  path
+}
+
+fn reviews(item: &Value) -> Vec<(u64, u64)> {
+  item["reviews"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|r| {
+      (
+        r["rating"].as_u64().unwrap(),
+        r["delta_t"].as_u64().unwrap(),
+      )
+    })
+    .collect()
+}
+
+#[test]
+fn convert_prints_fsrs_items() {
+  let items = json(fsrs(&["convert", FIXTURE, "--timezone", "UTC"]));
+  let items: Vec<_> = items.as_array().unwrap().iter().map(reviews).collect();
+  assert_eq!(items, [vec![(3, 0), (3, 1)], vec![(1, 0), (3, 0), (2, 2)]]);
+}
+
+#[test]
+fn convert_skips_malformed_rows_in_lenient_mode() {
+  let path = write_revlog(
+    "malformed.csv",
+    "card_id,review_time,review_rating,review_state,review_duration
+1,1700049600000,3,1,8000
+1,1700136000000,five,2,4000
+",
+  );
+  let path = path.to_str().unwrap();
+  fsrs(&["convert", path])
+    .failure()
+    .stderr(contains("fsrs: CSV deserialization error at line 3"));
+  fsrs(&["convert", path, "--lenient"])
+    .success()
+    .stdout("[]\n")
+    .stderr(contains("skipped line 3"));
+}
+
+#[test]
+fn optimize_prints_parameters() {
+  let path = write_revlog("optimize.csv", &synthetic_revlog(300));
+  let parameters = json(fsrs(&["optimize", path.to_str().unwrap()]));
+  let parameters = parameters.as_array().unwrap();
+  assert_eq!(parameters.len(), fsrs::DEFAULT_PARAMETERS.len());
+  assert!(parameters.iter().all(|w| w.as_f64().unwrap().is_finite()));
+}
+
+#[test]
+fn evaluate_prints_metrics() {
+  let path = write_revlog("evaluate.csv", &synthetic_revlog(300));
+  let metrics = json(fsrs(&["evaluate", path.to_str().unwrap()]));
+  for metric in ["log_loss", "rmse_bins"] {
+    let value = metrics[metric].as_f64().unwrap();
+    assert!(value.is_finite() && value > 0.0, "{metric} = {value}");
+  }
+}
+
+#[test]
+fn steps_prints_step_stats() {
+  let stats = json(fsrs(&["steps", FIXTURE]));
+  assert_eq!(
+    stats["recommended_learning_steps"],
+    Value::Array(Vec::new())
+  );
+  assert!(stats["again"].is_null());
+
+  fsrs(&["steps", FIXTURE, "--decay", "2"])
+    .failure()
+    .stderr(contains("fsrs: decay must be finite"));
+}
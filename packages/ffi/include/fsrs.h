@@ -0,0 +1,147 @@
+#ifndef FSRS_H
+#define FSRS_H
+
+/* Generated by cbindgen from packages/ffi/src. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Number of FSRS-6 parameters
+#define FSRS_PARAMETER_COUNT 21
+
+typedef enum FsrsStatus {
+  FSRS_STATUS_OK = 0,
+  // A null pointer, an out-of-range value or a malformed revlog was passed
+  FSRS_STATUS_INVALID_ARGUMENT = 1,
+  // The computation failed
+  FSRS_STATUS_FAILED = 2,
+} FsrsStatus;
+
+// A scheduler with fixed parameters
+typedef struct FsrsModel FsrsModel;
+
+typedef struct FsrsMemoryState {
+  float stability;
+  float difficulty;
+} FsrsMemoryState;
+
+typedef struct FsrsItemState {
+  FsrsMemoryState memory;
+  // Interval in days
+  float interval;
+} FsrsItemState;
+
+typedef struct FsrsNextStates {
+  FsrsItemState again;
+  FsrsItemState hard;
+  FsrsItemState good;
+  FsrsItemState easy;
+} FsrsNextStates;
+
+typedef struct FsrsReview {
+  // 1 (Again) to 4 (Easy)
+  uint32_t rating;
+  // Days since the previous review
+  uint32_t delta_t;
+} FsrsReview;
+
+typedef struct FsrsModelEvaluation {
+  float log_loss;
+  float rmse_bins;
+} FsrsModelEvaluation;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Message of the last failed call on this thread, or null.
+// The string is owned by the library and valid until the next failing call.
+const char *fsrs_last_error(void);
+
+// Create a scheduler from `len` parameters, or the default parameters when `len` is 0.
+// Free it with `fsrs_model_free`.
+//
+// # Safety
+// `parameters` must point to `len` floats and `out` must be valid for writes.
+FsrsStatus fsrs_model_new(const float *parameters, size_t len, FsrsModel **out);
+
+// Free a scheduler created by `fsrs_model_new`. Null is ignored.
+//
+// # Safety
+// `model` must come from `fsrs_model_new` and not have been freed yet.
+void fsrs_model_free(FsrsModel *model);
+
+// Memory states and intervals after each possible rating.
+// `current_memory_state` is null for a new card.
+//
+// # Safety
+// `model` must be a live scheduler, `current_memory_state` null or valid for
+// reads and `out` valid for writes.
+FsrsStatus fsrs_next_states(const FsrsModel *model,
+                            const FsrsMemoryState *current_memory_state,
+                            float desired_retention,
+                            uint32_t days_elapsed,
+                            FsrsNextStates *out);
+
+// Replay `len` reviews and return the resulting memory state.
+// `starting_state` is null, or seeds the first review.
+//
+// # Safety
+// `model` must be a live scheduler, `reviews` must point to `len` reviews,
+// `starting_state` must be null or valid for reads and `out` valid for writes.
+FsrsStatus fsrs_memory_state(const FsrsModel *model,
+                             const FsrsReview *reviews,
+                             size_t len,
+                             const FsrsMemoryState *starting_state,
+                             FsrsMemoryState *out);
+
+// Evaluate the scheduler's parameters on a revlog CSV.
+// Day boundaries are computed in the IANA `timezone`.
+//
+// # Safety
+// `model` must be a live scheduler, `csv` must point to `csv_len` bytes,
+// `timezone` must be a NUL-terminated string and `out` valid for writes.
+FsrsStatus fsrs_model_evaluate(const FsrsModel *model,
+                               const uint8_t *csv,
+                               size_t csv_len,
+                               int64_t next_day_starts_at,
+                               const char *timezone,
+                               FsrsModelEvaluation *out);
+
+// Compute parameters for a revlog CSV into `out_parameters`, which holds
+// `capacity` floats (`FSRS_PARAMETER_COUNT` is enough). `out_len` receives the
+// number of parameters, also when `capacity` is too small, which is rejected
+// before any training.
+//
+// # Safety
+// `csv` must point to `csv_len` bytes, `timezone` must be a NUL-terminated
+// string, `out_parameters` must be valid for `capacity` writes and `out_len`
+// valid for writes.
+FsrsStatus fsrs_compute_parameters(const uint8_t *csv,
+                                   size_t csv_len,
+                                   int64_t next_day_starts_at,
+                                   const char *timezone,
+                                   bool enable_short_term,
+                                   float *out_parameters,
+                                   size_t capacity,
+                                   size_t *out_len);
+
+// Evaluate the optimizer on a revlog CSV using time-series splits.
+//
+// # Safety
+// `csv` must point to `csv_len` bytes, `timezone` must be a NUL-terminated
+// string and `out` valid for writes.
+FsrsStatus fsrs_evaluate_with_time_series_splits(const uint8_t *csv,
+                                                 size_t csv_len,
+                                                 int64_t next_day_starts_at,
+                                                 const char *timezone,
+                                                 bool enable_short_term,
+                                                 FsrsModelEvaluation *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FSRS_H */
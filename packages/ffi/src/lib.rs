@@ -0,0 +1,568 @@
+//! C ABI for the FSRS scheduler and optimizer.
+//!
+//! Every function returns an `FsrsStatus`; on failure `fsrs_last_error`
+//! describes what went wrong. The header in `include/fsrs.h` is generated
+//! with cbindgen, see `cbindgen.toml`.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use fsrs_binding_core::CsvSchema;
+
+/// Number of FSRS-6 parameters
+pub const FSRS_PARAMETER_COUNT: usize = fsrs::DEFAULT_PARAMETERS.len();
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsrsStatus {
+  Ok = 0,
+  /// A null pointer, an out-of-range value or a malformed revlog was passed
+  InvalidArgument = 1,
+  /// The computation failed
+  Failed = 2,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsrsMemoryState {
+  pub stability: f32,
+  pub difficulty: f32,
+}
+
+impl From<fsrs::MemoryState> for FsrsMemoryState {
+  fn from(state: fsrs::MemoryState) -> Self {
+    Self {
+      stability: state.stability,
+      difficulty: state.difficulty,
+    }
+  }
+}
+
+impl From<FsrsMemoryState> for fsrs::MemoryState {
+  fn from(state: FsrsMemoryState) -> Self {
+    Self {
+      stability: state.stability,
+      difficulty: state.difficulty,
+    }
+  }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsrsItemState {
+  pub memory: FsrsMemoryState,
+  /// Interval in days
+  pub interval: f32,
+}
+
+impl From<fsrs::ItemState> for FsrsItemState {
+  fn from(state: fsrs::ItemState) -> Self {
+    Self {
+      memory: state.memory.into(),
+      interval: state.interval,
+    }
+  }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsrsNextStates {
+  pub again: FsrsItemState,
+  pub hard: FsrsItemState,
+  pub good: FsrsItemState,
+  pub easy: FsrsItemState,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsrsReview {
+  /// 1 (Again) to 4 (Easy)
+  pub rating: u32,
+  /// Days since the previous review
+  pub delta_t: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsrsModelEvaluation {
+  pub log_loss: f32,
+  pub rmse_bins: f32,
+}
+
+/// A scheduler with fixed parameters
+pub struct FsrsModel {
+  inner: fsrs::FSRS,
+}
+
+thread_local! {
+  static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+enum Error {
+  InvalidArgument(String),
+  Failed(String),
+}
+
+/// Revlog errors are all caused by the input data
+impl From<fsrs_binding_core::Error> for Error {
+  fn from(e: fsrs_binding_core::Error) -> Self {
+    Self::InvalidArgument(e.to_string())
+  }
+}
+
+/// Run `f`, turning errors and panics into a status and the last error message
+fn run(f: impl FnOnce() -> Result<(), Error>) -> FsrsStatus {
+  let (status, message) = match catch_unwind(AssertUnwindSafe(f)) {
+    Ok(Ok(())) => return FsrsStatus::Ok,
+    Ok(Err(Error::InvalidArgument(message))) => (FsrsStatus::InvalidArgument, message),
+    Ok(Err(Error::Failed(message))) => (FsrsStatus::Failed, message),
+    Err(_) => (FsrsStatus::Failed, "FSRS panicked".to_string()),
+  };
+  LAST_ERROR.with(|e| *e.borrow_mut() = CString::new(message).ok());
+  status
+}
+
+unsafe fn slice<'a, T>(ptr: *const T, len: usize, name: &str) -> Result<&'a [T], Error> {
+  if len == 0 {
+    return Ok(&[]);
+  }
+  if ptr.is_null() {
+    return Err(Error::InvalidArgument(format!("{name} is null")));
+  }
+  Ok(unsafe { std::slice::from_raw_parts(ptr, len) })
+}
+
+unsafe fn reference<'a, T>(ptr: *const T, name: &str) -> Result<&'a T, Error> {
+  unsafe { ptr.as_ref() }.ok_or_else(|| Error::InvalidArgument(format!("{name} is null")))
+}
+
+unsafe fn write<T>(ptr: *mut T, value: T, name: &str) -> Result<(), Error> {
+  let out =
+    unsafe { ptr.as_mut() }.ok_or_else(|| Error::InvalidArgument(format!("{name} is null")))?;
+  *out = value;
+  Ok(())
+}
+
+/// FSRS items from a revlog CSV with the default
+/// `card_id,review_time,review_rating,review_state,review_duration` header
+unsafe fn revlog_items(
+  csv: *const u8,
+  csv_len: usize,
+  next_day_starts_at: i64,
+  timezone: *const c_char,
+) -> Result<Vec<fsrs::FSRSItem>, Error> {
+  let data = unsafe { slice(csv, csv_len, "csv") }?;
+  if timezone.is_null() {
+    return Err(Error::InvalidArgument("timezone is null".to_string()));
+  }
+  let timezone = unsafe { CStr::from_ptr(timezone) }
+    .to_str()
+    .map_err(|e| Error::InvalidArgument(format!("timezone is not UTF-8: {e}")))?;
+  let timezone = fsrs_binding_core::time_zone(timezone)?;
+
+  let (revlogs, _) = fsrs_binding_core::read_revlog_csv(data, &CsvSchema::default(), false)?;
+  Ok(fsrs_binding_core::convert_revlogs_to_fsrs_items(
+    revlogs,
+    next_day_starts_at,
+    &timezone,
+  )?)
+}
+
+fn training_input(
+  train_set: Vec<fsrs::FSRSItem>,
+  enable_short_term: bool,
+) -> fsrs::ComputeParametersInput {
+  fsrs::ComputeParametersInput {
+    card_ids: None,
+    train_set,
+    progress: None,
+    enable_short_term,
+    num_relearning_steps: None,
+    training_config: None,
+  }
+}
+
+/// Message of the last failed call on this thread, or null.
+/// The string is owned by the library and valid until the next failing call.
+#[unsafe(no_mangle)]
+pub extern "C" fn fsrs_last_error() -> *const c_char {
+  LAST_ERROR.with(|e| {
+    e.borrow()
+      .as_ref()
+      .map_or(std::ptr::null(), |message| message.as_ptr())
+  })
+}
+
+/// Create a scheduler from `len` parameters, or the default parameters when `len` is 0.
+/// Free it with `fsrs_model_free`.
+///
+/// # Safety
+/// `parameters` must point to `len` floats and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_model_new(
+  parameters: *const f32,
+  len: usize,
+  out: *mut *mut FsrsModel,
+) -> FsrsStatus {
+  run(|| {
+    let parameters = unsafe { slice(parameters, len, "parameters") }?;
+    let inner = if parameters.is_empty() {
+      fsrs::FSRS::default()
+    } else {
+      fsrs::FSRS::new(parameters)
+        .map_err(|e| Error::InvalidArgument(format!("Failed to create FSRS: {e}")))?
+    };
+    unsafe { write(out, Box::into_raw(Box::new(FsrsModel { inner })), "out") }
+  })
+}
+
+/// Free a scheduler created by `fsrs_model_new`. Null is ignored.
+///
+/// # Safety
+/// `model` must come from `fsrs_model_new` and not have been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_model_free(model: *mut FsrsModel) {
+  if !model.is_null() {
+    drop(unsafe { Box::from_raw(model) });
+  }
+}
+
+/// Memory states and intervals after each possible rating.
+/// `current_memory_state` is null for a new card.
+///
+/// # Safety
+/// `model` must be a live scheduler, `current_memory_state` null or valid for
+/// reads and `out` valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_next_states(
+  model: *const FsrsModel,
+  current_memory_state: *const FsrsMemoryState,
+  desired_retention: f32,
+  days_elapsed: u32,
+  out: *mut FsrsNextStates,
+) -> FsrsStatus {
+  run(|| {
+    let model = unsafe { reference(model, "model") }?;
+    let current = unsafe { current_memory_state.as_ref() }.map(|&state| state.into());
+    let states = model
+      .inner
+      .next_states(current, desired_retention, days_elapsed)
+      .map_err(|e| Error::Failed(format!("Failed to get next states: {e}")))?;
+    let next_states = FsrsNextStates {
+      again: states.again.into(),
+      hard: states.hard.into(),
+      good: states.good.into(),
+      easy: states.easy.into(),
+    };
+    unsafe { write(out, next_states, "out") }
+  })
+}
+
+/// Replay `len` reviews and return the resulting memory state.
+/// `starting_state` is null, or seeds the first review.
+///
+/// # Safety
+/// `model` must be a live scheduler, `reviews` must point to `len` reviews,
+/// `starting_state` must be null or valid for reads and `out` valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_memory_state(
+  model: *const FsrsModel,
+  reviews: *const FsrsReview,
+  len: usize,
+  starting_state: *const FsrsMemoryState,
+  out: *mut FsrsMemoryState,
+) -> FsrsStatus {
+  run(|| {
+    let model = unsafe { reference(model, "model") }?;
+    let reviews = unsafe { slice(reviews, len, "reviews") }?;
+    let item = fsrs::FSRSItem {
+      reviews: reviews
+        .iter()
+        .map(|review| fsrs::FSRSReview {
+          rating: review.rating,
+          delta_t: review.delta_t,
+        })
+        .collect(),
+    };
+    let starting_state = unsafe { starting_state.as_ref() }.map(|&state| state.into());
+    let state = model
+      .inner
+      .memory_state(item, starting_state)
+      .map_err(|e| Error::Failed(format!("Failed to compute memory state: {e}")))?;
+    unsafe { write(out, state.into(), "out") }
+  })
+}
+
+/// Evaluate the scheduler's parameters on a revlog CSV.
+/// Day boundaries are computed in the IANA `timezone`.
+///
+/// # Safety
+/// `model` must be a live scheduler, `csv` must point to `csv_len` bytes,
+/// `timezone` must be a NUL-terminated string and `out` valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_model_evaluate(
+  model: *const FsrsModel,
+  csv: *const u8,
+  csv_len: usize,
+  next_day_starts_at: i64,
+  timezone: *const c_char,
+  out: *mut FsrsModelEvaluation,
+) -> FsrsStatus {
+  run(|| {
+    let model = unsafe { reference(model, "model") }?;
+    let items = unsafe { revlog_items(csv, csv_len, next_day_starts_at, timezone) }?;
+    let evaluation = model
+      .inner
+      .evaluate(fsrs_binding_core::prepare_items(items), |_| true)
+      .map_err(|e| Error::Failed(format!("Evaluation failed: {e}")))?;
+    let evaluation = FsrsModelEvaluation {
+      log_loss: evaluation.log_loss,
+      rmse_bins: evaluation.rmse_bins,
+    };
+    unsafe { write(out, evaluation, "out") }
+  })
+}
+
+/// Compute parameters for a revlog CSV into `out_parameters`, which holds
+/// `capacity` floats (`FSRS_PARAMETER_COUNT` is enough). `out_len` receives the
+/// number of parameters, also when `capacity` is too small, which is rejected
+/// before any training.
+///
+/// # Safety
+/// `csv` must point to `csv_len` bytes, `timezone` must be a NUL-terminated
+/// string, `out_parameters` must be valid for `capacity` writes and `out_len`
+/// valid for writes.
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn fsrs_compute_parameters(
+  csv: *const u8,
+  csv_len: usize,
+  next_day_starts_at: i64,
+  timezone: *const c_char,
+  enable_short_term: bool,
+  out_parameters: *mut f32,
+  capacity: usize,
+  out_len: *mut usize,
+) -> FsrsStatus {
+  run(|| {
+    unsafe { write(out_len, FSRS_PARAMETER_COUNT, "out_len") }?;
+    if capacity < FSRS_PARAMETER_COUNT {
+      return Err(Error::InvalidArgument(format!(
+        "out_parameters holds {capacity} values, {FSRS_PARAMETER_COUNT} needed"
+      )));
+    }
+    if out_parameters.is_null() {
+      return Err(Error::InvalidArgument("out_parameters is null".to_string()));
+    }
+
+    let items = unsafe { revlog_items(csv, csv_len, next_day_starts_at, timezone) }?;
+    let parameters = fsrs::compute_parameters(training_input(items, enable_short_term))
+      .map_err(|e| Error::Failed(format!("compute_parameters failed: {e}")))?;
+    assert_eq!(parameters.len(), FSRS_PARAMETER_COUNT);
+    unsafe {
+      std::ptr::copy_nonoverlapping(parameters.as_ptr(), out_parameters, parameters.len());
+    }
+    Ok(())
+  })
+}
+
+/// Evaluate the optimizer on a revlog CSV using time-series splits.
+///
+/// # Safety
+/// `csv` must point to `csv_len` bytes, `timezone` must be a NUL-terminated
+/// string and `out` valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_evaluate_with_time_series_splits(
+  csv: *const u8,
+  csv_len: usize,
+  next_day_starts_at: i64,
+  timezone: *const c_char,
+  enable_short_term: bool,
+  out: *mut FsrsModelEvaluation,
+) -> FsrsStatus {
+  run(|| {
+    let items = unsafe { revlog_items(csv, csv_len, next_day_starts_at, timezone) }?;
+    let items = fsrs_binding_core::prepare_items(items);
+    let evaluation =
+      fsrs::evaluate_with_time_series_splits(training_input(items, enable_short_term), |_| true)
+        .map_err(|e| Error::Failed(format!("evaluate_with_time_series_splits failed: {e}")))?;
+    let evaluation = FsrsModelEvaluation {
+      log_loss: evaluation.log_loss,
+      rmse_bins: evaluation.rmse_bins,
+    };
+    unsafe { write(out, evaluation, "out") }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use fsrs_binding_core::testing::synthetic_revlog;
+
+  use super::*;
+
+  fn last_error() -> String {
+    let message = fsrs_last_error();
+    assert!(!message.is_null());
+    unsafe { CStr::from_ptr(message) }
+      .to_str()
+      .unwrap()
+      .to_string()
+  }
+
+  fn default_model() -> *mut FsrsModel {
+    let mut model = std::ptr::null_mut();
+    let status = unsafe { fsrs_model_new(std::ptr::null(), 0, &mut model) };
+    assert_eq!(status, FsrsStatus::Ok);
+    assert!(!model.is_null());
+    model
+  }
+
+  fn next_states(model: *const FsrsModel, state: Option<&FsrsMemoryState>) -> FsrsNextStates {
+    let state = state.map_or(std::ptr::null(), |state| state as *const _);
+    let mut out = std::mem::MaybeUninit::uninit();
+    let status = unsafe { fsrs_next_states(model, state, 0.9, 3, out.as_mut_ptr()) };
+    assert_eq!(status, FsrsStatus::Ok);
+    unsafe { out.assume_init() }
+  }
+
+  fn memory_state(model: *const FsrsModel, reviews: &[FsrsReview]) -> FsrsMemoryState {
+    let mut out = std::mem::MaybeUninit::uninit();
+    let status = unsafe {
+      fsrs_memory_state(
+        model,
+        reviews.as_ptr(),
+        reviews.len(),
+        std::ptr::null(),
+        out.as_mut_ptr(),
+      )
+    };
+    assert_eq!(status, FsrsStatus::Ok);
+    unsafe { out.assume_init() }
+  }
+
+  fn assert_same_state(a: FsrsMemoryState, b: FsrsMemoryState) {
+    assert!((a.stability - b.stability).abs() < 1e-4, "{a:?} != {b:?}");
+    assert!((a.difficulty - b.difficulty).abs() < 1e-4, "{a:?} != {b:?}");
+  }
+
+  #[test]
+  fn parameter_count_matches_the_header() {
+    let header = include_str!("../include/fsrs.h");
+    let define = format!("#define FSRS_PARAMETER_COUNT {FSRS_PARAMETER_COUNT}\n");
+    assert!(header.contains(&define));
+  }
+
+  #[test]
+  fn null_out_is_rejected() {
+    let status = unsafe { fsrs_model_new(std::ptr::null(), 0, std::ptr::null_mut()) };
+    assert_eq!(status, FsrsStatus::InvalidArgument);
+    assert_eq!(last_error(), "out is null");
+
+    let model = default_model();
+    let status = unsafe { fsrs_next_states(model, std::ptr::null(), 0.9, 0, std::ptr::null_mut()) };
+    assert_eq!(status, FsrsStatus::InvalidArgument);
+    assert_eq!(last_error(), "out is null");
+    unsafe { fsrs_model_free(model) };
+  }
+
+  #[test]
+  fn null_parameters_with_len_are_rejected() {
+    let mut model = std::ptr::null_mut();
+    let status = unsafe { fsrs_model_new(std::ptr::null(), 3, &mut model) };
+    assert_eq!(status, FsrsStatus::InvalidArgument);
+    assert_eq!(last_error(), "parameters is null");
+    assert!(model.is_null());
+  }
+
+  #[test]
+  fn zero_len_uses_the_default_parameters() {
+    let model = default_model();
+    let mut explicit = std::ptr::null_mut();
+    let parameters = fsrs::DEFAULT_PARAMETERS;
+    let status = unsafe { fsrs_model_new(parameters.as_ptr(), parameters.len(), &mut explicit) };
+    assert_eq!(status, FsrsStatus::Ok);
+
+    let (a, b) = (next_states(model, None), next_states(explicit, None));
+    for (a, b) in [(a.again, b.again), (a.good, b.good), (a.easy, b.easy)] {
+      assert_same_state(a.memory, b.memory);
+      assert_eq!(a.interval, b.interval);
+    }
+    unsafe {
+      fsrs_model_free(model);
+      fsrs_model_free(explicit);
+    }
+  }
+
+  #[test]
+  fn failures_set_the_last_error() {
+    let model = default_model();
+    let timezone = c"Mars/Olympus_Mons";
+    let csv = synthetic_revlog(1);
+    let mut out = std::mem::MaybeUninit::uninit();
+    let status = unsafe {
+      fsrs_model_evaluate(
+        model,
+        csv.as_ptr(),
+        csv.len(),
+        4,
+        timezone.as_ptr(),
+        out.as_mut_ptr(),
+      )
+    };
+    assert_eq!(status, FsrsStatus::InvalidArgument);
+    assert!(last_error().contains("Mars/Olympus_Mons"));
+    unsafe { fsrs_model_free(model) };
+  }
+
+  #[test]
+  fn next_states_round_trip_through_memory_state() {
+    let model = default_model();
+    let good = FsrsReview {
+      rating: 3,
+      delta_t: 0,
+    };
+    let first = next_states(model, None).good.memory;
+    assert_same_state(first, memory_state(model, &[good]));
+
+    let second = next_states(model, Some(&first)).good.memory;
+    let replayed = memory_state(
+      model,
+      &[
+        good,
+        FsrsReview {
+          rating: 3,
+          delta_t: 3,
+        },
+      ],
+    );
+    assert_same_state(second, replayed);
+    unsafe { fsrs_model_free(model) };
+  }
+
+  #[test]
+  fn compute_parameters_reports_the_needed_capacity() {
+    let csv = synthetic_revlog(1);
+    let mut parameters = [0.0; 1];
+    let mut len = 0;
+    let status = unsafe {
+      fsrs_compute_parameters(
+        csv.as_ptr(),
+        csv.len(),
+        4,
+        c"UTC".as_ptr(),
+        true,
+        parameters.as_mut_ptr(),
+        parameters.len(),
+        &mut len,
+      )
+    };
+    assert_eq!(status, FsrsStatus::InvalidArgument);
+    assert_eq!(len, FSRS_PARAMETER_COUNT);
+    assert_eq!(
+      last_error(),
+      format!("out_parameters holds 1 values, {FSRS_PARAMETER_COUNT} needed")
+    );
+    assert_eq!(parameters, [0.0]);
+  }
+}